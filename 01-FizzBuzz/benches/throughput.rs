@@ -0,0 +1,67 @@
+//! FizzBuzzの出力速度の比較
+//!
+//! `cargo bench --bench throughput -- <N>` で実行します. (Nの既定値は10,000,000)
+//! 出力先は実際には捨てていますが, `println!`相当の版は改行ごとにフラッシュされます.
+
+use std::{
+    hint::black_box,
+    io::{self, LineWriter, Write},
+    time::Instant,
+};
+
+use fizz_buzz::{write_fizz_buzz, FizzBuzz};
+
+/// 書き込まれたバイト数だけを数える出力先
+#[derive(Default)]
+struct Counter {
+    bytes: u64,
+}
+
+impl Write for Counter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.bytes += black_box(buf).len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// `println!`と同様, 1行ごとに`to_string()`相当の整形とフラッシュを行う
+fn line_by_line(n: u64, out: &mut Counter) -> io::Result<()> {
+    let mut out = LineWriter::new(out);
+    for value in FizzBuzz::new(n) {
+        writeln!(out, "{}", value)?;
+    }
+    out.flush()
+}
+
+fn buffered(n: u64, out: &mut Counter) -> io::Result<()> {
+    write_fizz_buzz(out, 1..=n)
+}
+
+fn measure(name: &str, n: u64, f: fn(u64, &mut Counter) -> io::Result<()>) {
+    let mut out = Counter::default();
+    let started = Instant::now();
+    f(n, &mut out).expect("write to counter never fails");
+    let elapsed = started.elapsed().as_secs_f64();
+
+    println!(
+        "{:<14} {:>12} bytes {:>8.3} s {:>10.1} MiB/s",
+        name,
+        out.bytes,
+        elapsed,
+        out.bytes as f64 / elapsed / (1024.0 * 1024.0)
+    );
+}
+
+fn main() {
+    let n = std::env::args()
+        .skip(1)
+        .find_map(|arg| arg.parse().ok())
+        .unwrap_or(10_000_000);
+
+    measure("line-by-line", n, line_by_line);
+    measure("buffered", n, buffered);
+}
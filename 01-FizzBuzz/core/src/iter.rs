@@ -0,0 +1,109 @@
+use core::{iter::FusedIterator, ops::RangeInclusive};
+
+use crate::FizzBuzzValue;
+
+/// 指定範囲のFizzBuzz値を順に返すイテレータ
+///
+/// 残りの範囲を先頭の値と残り個数で保持しているため, `nth`や`len`はO(1)で計算できます.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FizzBuzz {
+    front: u64,
+    // `0..=u64::MAX`の個数 (2^64) も表せるよう, u64より広く持つ
+    remaining: u128,
+}
+
+impl FizzBuzz {
+    /// 1から`n`までのFizzBuzz
+    pub fn new(n: u64) -> Self {
+        Self {
+            front: 1,
+            remaining: u128::from(n),
+        }
+    }
+
+    /// 任意の範囲のFizzBuzz. `0..=u64::MAX`のような範囲も末尾まで返します
+    ///
+    /// 個数がusizeに収まらない範囲では, `size_hint`の上限は`None`になり, `len`はパニックします.
+    pub fn range(range: RangeInclusive<u64>) -> Self {
+        let (start, end) = range.into_inner();
+        let remaining = if start > end {
+            0
+        } else {
+            u128::from(end - start) + 1
+        };
+
+        Self {
+            front: start,
+            remaining,
+        }
+    }
+}
+
+impl Iterator for FizzBuzz {
+    type Item = FizzBuzzValue;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let n = self.front;
+        self.front = self.front.wrapping_add(1);
+        self.remaining -= 1;
+
+        Some(FizzBuzzValue::new(n))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match usize::try_from(self.remaining) {
+            Ok(len) => (len, Some(len)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+
+    fn count(self) -> usize {
+        self.len()
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let n = n as u128;
+        if n >= self.remaining {
+            self.front = self.front.wrapping_add(self.remaining as u64);
+            self.remaining = 0;
+            return None;
+        }
+        self.front += n as u64;
+        self.remaining -= n;
+
+        self.next()
+    }
+}
+
+impl DoubleEndedIterator for FizzBuzz {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        Some(FizzBuzzValue::new(self.front + self.remaining as u64))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        let n = n as u128;
+        if n >= self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+        self.remaining -= n;
+
+        self.next_back()
+    }
+}
+
+impl ExactSizeIterator for FizzBuzz {}
+
+impl FusedIterator for FizzBuzz {}
@@ -0,0 +1,35 @@
+//! # FizzBuzz core
+//!
+//! マイコンなどでも動かせるよう, 標準ライブラリとヒープ割り当てを使わないFizzBuzzの判定と整形の処理です.
+//! `fizz-buzz`クレートはこれを標準ライブラリの入出力で包んだものです.
+//!
+//! - [`FizzBuzzValue`] : 1つの数値に対するFizzBuzz値. 文字列から解析でき, `serde`機能を有効にするとシリアライズもできる
+//! - [`FizzBuzz`] : 指定範囲のFizzBuzz値を順に返すイテレータ
+//! - [`fizz_buzz_value`], [`FizzBuzzTable`] : コンパイル時に評価できるFizzBuzz値と, その表
+//! - [`Lines`] : 指定範囲のFizzBuzzを呼び出し側のバッファへ1行ずつ書き込むカーソル
+//! - [`write_lines`] : 指定範囲のFizzBuzzを[`core::fmt::Write`]へ書き出す処理
+//! - [`SliceWriter`] : 呼び出し側のバッファへ書き込む[`core::fmt::Write`]
+//!
+//! ```
+//! use core::fmt::Write;
+//! use fizz_buzz_core::{fizz_buzz_value, SliceWriter};
+//!
+//! let mut buf = [0; 16];
+//! let mut out = SliceWriter::new(&mut buf);
+//! write!(out, "{} {}", fizz_buzz_value(14), fizz_buzz_value(15)).unwrap();
+//! assert_eq!(out.as_str(), "14 FizzBuzz");
+//! ```
+
+#![no_std]
+
+mod iter;
+mod lines;
+mod slice;
+mod table;
+mod value;
+
+pub use iter::FizzBuzz;
+pub use lines::{write_lines, Lines, MAX_LINE_LEN};
+pub use slice::SliceWriter;
+pub use table::FizzBuzzTable;
+pub use value::{fizz_buzz_value, FizzBuzzValue, ParseValueError};
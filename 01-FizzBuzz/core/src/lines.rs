@@ -0,0 +1,186 @@
+//! 指定範囲のFizzBuzzを, 呼び出し側のバッファへ1行ずつ書き込む処理
+//!
+//! 数値の10進表現は整形せず, ASCIIのまま保持した各桁をその場で繰り上げます.
+//! `n % 15 == 1`から始まる15個の周期は, バッファに余裕があればまとめて書き込みます.
+
+use core::{fmt, ops::RangeInclusive, str};
+
+/// 1行の最大のバイト数 (u64の最大値の20桁と改行)
+pub const MAX_LINE_LEN: usize = 21;
+
+/// 1周期分を書き込むのに必要なバイト数の上限
+const CYCLE_LEN: usize = 15 * MAX_LINE_LEN;
+
+/// `n % 15`ごとの出力内容. `None`は数値をそのまま出力することを表す
+const CYCLE: [Option<&[u8]>; 15] = [
+    Some(b"FizzBuzz\n"),
+    None,
+    None,
+    Some(b"Fizz\n"),
+    None,
+    Some(b"Buzz\n"),
+    Some(b"Fizz\n"),
+    None,
+    None,
+    Some(b"Fizz\n"),
+    Some(b"Buzz\n"),
+    None,
+    Some(b"Fizz\n"),
+    None,
+    None,
+];
+
+/// 10進数の各桁をASCIIのまま保持し, その場で繰り上げるカウンタ
+#[derive(Debug, Clone)]
+struct Digits {
+    // 右詰めで格納し, 末尾は常に改行
+    buf: [u8; MAX_LINE_LEN],
+    start: usize,
+}
+
+impl Digits {
+    fn new(n: u64) -> Self {
+        let mut buf = [b'0'; MAX_LINE_LEN];
+        buf[MAX_LINE_LEN - 1] = b'\n';
+        let mut start = MAX_LINE_LEN - 1;
+        let mut n = n;
+        loop {
+            start -= 1;
+            buf[start] = b'0' + (n % 10) as u8;
+            n /= 10;
+            if n == 0 {
+                break;
+            }
+        }
+
+        Self { buf, start }
+    }
+
+    /// 改行を含む現在の数値のバイト列
+    fn as_line(&self) -> &[u8] {
+        &self.buf[self.start..]
+    }
+
+    fn increment(&mut self) {
+        let mut i = MAX_LINE_LEN - 2;
+        loop {
+            if self.buf[i] == b'9' {
+                self.buf[i] = b'0';
+                if i == self.start {
+                    self.start -= 1;
+                    self.buf[self.start] = b'1';
+                    return;
+                }
+                i -= 1;
+            } else {
+                self.buf[i] += 1;
+                return;
+            }
+        }
+    }
+}
+
+/// 指定範囲のFizzBuzzを, 呼び出し側のバッファへ1行ずつ書き込むカーソル
+///
+/// ```
+/// use fizz_buzz_core::{Lines, MAX_LINE_LEN};
+///
+/// let mut lines = Lines::new(1..=5);
+/// let mut buf = [0; MAX_LINE_LEN];
+/// let mut out = Vec::new();
+/// while !lines.is_done() {
+///     let len = lines.fill(&mut buf);
+///     out.extend_from_slice(&buf[..len]);
+/// }
+/// assert_eq!(out, b"1\n2\nFizz\n4\nBuzz\n");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Lines {
+    digits: Digits,
+    next: u64,
+    end: u64,
+    done: bool,
+}
+
+impl Lines {
+    pub fn new(range: RangeInclusive<u64>) -> Self {
+        let (start, end) = range.into_inner();
+        Self {
+            digits: Digits::new(start),
+            next: start,
+            end,
+            done: start > end,
+        }
+    }
+
+    /// 範囲の最後まで書き込んだか
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// `buf`に収まるだけの行を書き込み, 書き込んだバイト数を返す
+    ///
+    /// 行の途中では区切りません. `buf`が[`MAX_LINE_LEN`]バイト以上あれば, 書き終えていない限り必ず1行以上書き込みます.
+    pub fn fill(&mut self, buf: &mut [u8]) -> usize {
+        let mut len = 0;
+        while !self.done {
+            let n = self.next;
+            if n % 15 == 1 && self.end - n >= 14 && buf.len() - len >= CYCLE_LEN {
+                len += self.write_cycle(&mut buf[len..]);
+            } else {
+                let line = CYCLE[(n % 15) as usize].unwrap_or(self.digits.as_line());
+                let Some(dest) = buf.get_mut(len..len + line.len()) else {
+                    break;
+                };
+                dest.copy_from_slice(line);
+                len += line.len();
+            }
+
+            if self.next == self.end {
+                self.done = true;
+            } else {
+                self.next += 1;
+                self.digits.increment();
+            }
+        }
+
+        len
+    }
+
+    // `n % 15 == 1`から`n + 14`までの1周期を書き込む. 終了時は`n + 14`を指す
+    fn write_cycle(&mut self, buf: &mut [u8]) -> usize {
+        let mut len = 0;
+        for i in 1..=15 {
+            if i > 1 {
+                self.digits.increment();
+            }
+            let line = CYCLE[i % 15].unwrap_or(self.digits.as_line());
+            buf[len..len + line.len()].copy_from_slice(line);
+            len += line.len();
+        }
+        self.next += 14;
+
+        len
+    }
+}
+
+/// 指定範囲のFizzBuzzを1行ずつ`out`へ書き出す
+///
+/// ```
+/// use fizz_buzz_core::{write_lines, SliceWriter};
+///
+/// let mut buf = [0; 32];
+/// let mut out = SliceWriter::new(&mut buf);
+/// write_lines(&mut out, 13..=16).unwrap();
+/// assert_eq!(out.as_str(), "13\n14\nFizzBuzz\n16\n");
+/// ```
+pub fn write_lines<W: fmt::Write>(mut out: W, range: RangeInclusive<u64>) -> fmt::Result {
+    let mut lines = Lines::new(range);
+    let mut buf = [0; CYCLE_LEN];
+    while !lines.is_done() {
+        let len = lines.fill(&mut buf);
+        out.write_str(str::from_utf8(&buf[..len]).expect("lines are always ASCII"))?;
+    }
+
+    Ok(())
+}
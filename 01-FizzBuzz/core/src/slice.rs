@@ -0,0 +1,52 @@
+use core::{fmt, str};
+
+/// 呼び出し側のバッファへ書き込む[`fmt::Write`]
+///
+/// 収まらない文字列は書き込まず, `fmt::Error`を返します. それまでに書き込んだ内容はそのまま残ります.
+#[derive(Debug)]
+pub struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> SliceWriter<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    /// 書き込んだバイト数
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 残りの容量
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.len
+    }
+
+    /// 書き込んだ内容
+    pub fn as_str(&self) -> &str {
+        str::from_utf8(&self.buf[..self.len]).expect("only str is written")
+    }
+
+    /// 書き込んだ内容を消して, 先頭から書き直す
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl fmt::Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > self.buf.len() {
+            return Err(fmt::Error);
+        }
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
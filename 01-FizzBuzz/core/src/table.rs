@@ -0,0 +1,76 @@
+//! コンパイル時に求めるFizzBuzz値の表
+//!
+//! `static`に置いた表は実行時には計算されず, 参照は配列の添字アクセスのみになります.
+
+use core::{ops::Deref, slice};
+
+use crate::{fizz_buzz_value, FizzBuzzValue};
+
+/// 1からNまでのFizzBuzz値の表
+///
+/// ```
+/// use fizz_buzz_core::{FizzBuzzTable, FizzBuzzValue};
+///
+/// static TABLE: FizzBuzzTable<100> = FizzBuzzTable::new();
+///
+/// assert_eq!(TABLE.get(15), Some(FizzBuzzValue::FizzBuzz));
+/// assert_eq!(TABLE.get(101), None);
+/// assert_eq!(TABLE[3], FizzBuzzValue::Number(4));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FizzBuzzTable<const N: usize> {
+    values: [FizzBuzzValue; N],
+}
+
+impl<const N: usize> FizzBuzzTable<N> {
+    /// 1からNまでの値の配列
+    pub const VALUES: [FizzBuzzValue; N] = Self::new().values;
+
+    pub const fn new() -> Self {
+        let mut values = [FizzBuzzValue::FizzBuzz; N];
+        let mut i = 0;
+        while i < N {
+            values[i] = fizz_buzz_value(i as u64 + 1);
+            i += 1;
+        }
+
+        Self { values }
+    }
+
+    /// 数値`n`に対する値. `n`が1からNの範囲外の場合は`None`を返す
+    pub const fn get(&self, n: u64) -> Option<FizzBuzzValue> {
+        if n == 0 || n > N as u64 {
+            None
+        } else {
+            Some(self.values[(n - 1) as usize])
+        }
+    }
+
+    pub const fn as_array(&self) -> &[FizzBuzzValue; N] {
+        &self.values
+    }
+}
+
+impl<const N: usize> Default for FizzBuzzTable<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 添字は0始まり (`table[0]`は1の値) です
+impl<const N: usize> Deref for FizzBuzzTable<N> {
+    type Target = [FizzBuzzValue; N];
+
+    fn deref(&self) -> &Self::Target {
+        &self.values
+    }
+}
+
+impl<'a, const N: usize> IntoIterator for &'a FizzBuzzTable<N> {
+    type Item = &'a FizzBuzzValue;
+    type IntoIter = slice::Iter<'a, FizzBuzzValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
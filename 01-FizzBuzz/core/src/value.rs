@@ -0,0 +1,153 @@
+use core::{fmt, str::FromStr};
+
+/// 1つの数値に対するFizzBuzz値
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FizzBuzzValue {
+    Number(u64),
+    Fizz,
+    Buzz,
+    FizzBuzz,
+}
+
+impl FizzBuzzValue {
+    pub const fn new(n: u64) -> Self {
+        fizz_buzz_value(n)
+    }
+
+    // 単語の値の表記. 数値の場合は空文字列
+    fn word(&self) -> &'static str {
+        match self {
+            Self::Number(_) => "",
+            Self::Fizz => "Fizz",
+            Self::Buzz => "Buzz",
+            Self::FizzBuzz => "FizzBuzz",
+        }
+    }
+}
+
+/// 数値`n`に対するFizzBuzz値. コンパイル時にも評価できます
+///
+/// ```
+/// use fizz_buzz_core::{fizz_buzz_value, FizzBuzzValue};
+///
+/// const FIFTEEN: FizzBuzzValue = fizz_buzz_value(15);
+/// assert_eq!(FIFTEEN, FizzBuzzValue::FizzBuzz);
+/// ```
+pub const fn fizz_buzz_value(n: u64) -> FizzBuzzValue {
+    match (n % 3, n % 5) {
+        (0, 0) => FizzBuzzValue::FizzBuzz,
+        (0, _) => FizzBuzzValue::Fizz,
+        (_, 0) => FizzBuzzValue::Buzz,
+        (_, _) => FizzBuzzValue::Number(n),
+    }
+}
+
+impl From<u64> for FizzBuzzValue {
+    fn from(n: u64) -> Self {
+        Self::new(n)
+    }
+}
+
+impl fmt::Display for FizzBuzzValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(n) => write!(f, "{}", n),
+            word => f.write_str(word.word()),
+        }
+    }
+}
+
+/// `Fizz`, `Buzz`, `FizzBuzz`または10進数の数値を解析する. 単語の大文字と小文字は区別しない
+///
+/// [`Display`](fmt::Display)の出力を読み戻すためのもので, 数値が3や5の倍数かは確かめません. (`"3"`は`Number(3)`)
+///
+/// ```
+/// use fizz_buzz_core::FizzBuzzValue;
+///
+/// assert_eq!("Fizz".parse(), Ok(FizzBuzzValue::Fizz));
+/// assert_eq!("42".parse(), Ok(FizzBuzzValue::Number(42)));
+/// ```
+impl FromStr for FizzBuzzValue {
+    type Err = ParseValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseValueError::Empty);
+        }
+        // `+1`や` 1`は表示の形式と異なるため受け付けない
+        if s.bytes().all(|b| b.is_ascii_digit()) {
+            return s
+                .parse()
+                .map(Self::Number)
+                .map_err(|_| ParseValueError::TooLarge);
+        }
+
+        [Self::Fizz, Self::Buzz, Self::FizzBuzz]
+            .into_iter()
+            .find(|value| value.word().eq_ignore_ascii_case(s))
+            .ok_or(ParseValueError::Invalid)
+    }
+}
+
+/// FizzBuzz値の解析エラー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseValueError {
+    /// 空文字列
+    Empty,
+    /// 数値でも`Fizz`, `Buzz`, `FizzBuzz`でもない
+    Invalid,
+    /// u64に収まらない数値
+    TooLarge,
+}
+
+impl fmt::Display for ParseValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("cannot parse a FizzBuzz value from an empty string"),
+            Self::Invalid => f.write_str("expected a number, Fizz, Buzz or FizzBuzz"),
+            Self::TooLarge => write!(f, "number is too large (must be at most {})", u64::MAX),
+        }
+    }
+}
+
+impl core::error::Error for ParseValueError {}
+
+/// 表示と同じ文字列 (`"Fizz"`, `"42"`) として書き出す
+#[cfg(feature = "serde")]
+impl serde::Serialize for FizzBuzzValue {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// 文字列 (`"Fizz"`, `"42"`) のほか, 数値 (`42`) も受け付ける
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FizzBuzzValue {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = FizzBuzzValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a non-negative integer, \"Fizz\", \"Buzz\" or \"FizzBuzz\"")
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, n: u64) -> Result<Self::Value, E> {
+                Ok(FizzBuzzValue::Number(n))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, n: i64) -> Result<Self::Value, E> {
+                u64::try_from(n)
+                    .map(FizzBuzzValue::Number)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Signed(n), &self))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<Self::Value, E> {
+                s.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
@@ -0,0 +1,99 @@
+use std::fmt::Write;
+
+use fizz_buzz_core::{write_lines, FizzBuzz, Lines, SliceWriter, MAX_LINE_LEN};
+
+fn expected(start: u64, end: u64) -> String {
+    FizzBuzz::range(start..=end)
+        .map(|v| format!("{}\n", v))
+        .collect()
+}
+
+fn filled(start: u64, end: u64, buf_len: usize) -> String {
+    let mut lines = Lines::new(start..=end);
+    let mut buf = vec![0; buf_len];
+    let mut out = Vec::new();
+    while !lines.is_done() {
+        let len = lines.fill(&mut buf);
+        assert!(len > 0, "no progress with {} bytes", buf_len);
+        out.extend_from_slice(&buf[..len]);
+    }
+    String::from_utf8(out).unwrap()
+}
+
+// どのサイズのバッファでも, イテレータの出力と一致すれば可 (周期の途中開始, 桁上がり, u64の上限を含む)
+#[test]
+fn test_fill_matches_iter() {
+    for (start, end) in [
+        (1, 100),
+        (0, 31),
+        (7, 7),
+        (95, 1005),
+        (999_999_990, 1_000_000_020),
+        (u64::MAX - 40, u64::MAX),
+    ] {
+        for buf_len in [MAX_LINE_LEN, 40, 315, 1000, 1 << 16] {
+            assert_eq!(
+                filled(start, end, buf_len),
+                expected(start, end),
+                "{}..={} with {} bytes",
+                start,
+                end,
+                buf_len
+            );
+        }
+    }
+}
+
+// 行の途中で区切らず, 収まらない場合は何も書き込まなければ可
+#[test]
+fn test_fill_whole_lines() {
+    let mut lines = Lines::new(14..=16);
+    let mut buf = [0; 8];
+    assert_eq!(lines.fill(&mut buf), 3);
+    assert_eq!(&buf[..3], b"14\n");
+    assert_eq!(lines.fill(&mut buf), 0);
+    assert!(!lines.is_done());
+
+    let mut buf = [0; 12];
+    assert_eq!(lines.fill(&mut buf), 12);
+    assert_eq!(&buf, b"FizzBuzz\n16\n");
+    assert!(lines.is_done());
+    assert_eq!(lines.fill(&mut buf), 0);
+}
+
+// 空の範囲では何も書き込まなければ可
+#[test]
+fn test_fill_empty() {
+    let (start, end) = (10, 9);
+    let mut lines = Lines::new(start..=end);
+    assert!(lines.is_done());
+    assert_eq!(lines.fill(&mut [0; 64]), 0);
+}
+
+// fmt::Writeへの書き出しがイテレータの出力と一致すれば可
+#[test]
+fn test_write_lines() {
+    let mut out = String::new();
+    write_lines(&mut out, 1..=1000).unwrap();
+    assert_eq!(out, expected(1, 1000));
+}
+
+// バッファに収まらない書き込みはエラーになり, それまでの内容が残れば可
+#[test]
+fn test_slice_writer_overflow() {
+    let mut buf = [0; 10];
+    let mut out = SliceWriter::new(&mut buf);
+    assert!(out.is_empty());
+    write!(out, "Fizz,").unwrap();
+    assert!(write!(out, "FizzBuzz").is_err());
+    assert_eq!(out.as_str(), "Fizz,");
+    assert_eq!(out.remaining(), 5);
+
+    assert!(write_lines(&mut out, 1..=3).is_err());
+    assert_eq!(out.as_str(), "Fizz,");
+
+    out.clear();
+    write_lines(&mut out, 1..=3).unwrap();
+    assert_eq!(out.as_str(), "1\n2\nFizz\n");
+    assert_eq!(out.len(), 9);
+}
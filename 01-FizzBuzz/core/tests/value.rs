@@ -0,0 +1,45 @@
+use fizz_buzz_core::{FizzBuzz, FizzBuzzValue, ParseValueError};
+
+// 表示した文字列を解析すると, 元の値に戻れば可
+#[test]
+fn test_parse_round_trip() {
+    for value in FizzBuzz::new(100) {
+        let s = value.to_string();
+        assert_eq!(s.parse(), Ok(value), "{}", s);
+    }
+    let max = FizzBuzzValue::Number(u64::MAX);
+    assert_eq!(max.to_string().parse(), Ok(max));
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!("Fizz".parse(), Ok(FizzBuzzValue::Fizz));
+    assert_eq!("buzz".parse(), Ok(FizzBuzzValue::Buzz));
+    assert_eq!("FIZZBUZZ".parse(), Ok(FizzBuzzValue::FizzBuzz));
+    assert_eq!("42".parse(), Ok(FizzBuzzValue::Number(42)));
+    // 3の倍数かは確かめない
+    assert_eq!("3".parse(), Ok(FizzBuzzValue::Number(3)));
+    assert_eq!("007".parse(), Ok(FizzBuzzValue::Number(7)));
+}
+
+#[test]
+fn test_parse_error() {
+    let parse = |s: &str| s.parse::<FizzBuzzValue>();
+    assert_eq!(parse(""), Err(ParseValueError::Empty));
+    for s in ["Fizz Buzz", "Bazz", "-1", "+1", " 1", "1.0"] {
+        assert_eq!(parse(s), Err(ParseValueError::Invalid), "{}", s);
+    }
+    assert_eq!(
+        parse("18446744073709551616"),
+        Err(ParseValueError::TooLarge)
+    );
+
+    assert_eq!(
+        ParseValueError::Invalid.to_string(),
+        "expected a number, Fizz, Buzz or FizzBuzz"
+    );
+    assert_eq!(
+        ParseValueError::TooLarge.to_string(),
+        "number is too large (must be at most 18446744073709551615)"
+    );
+}
@@ -2,6 +2,10 @@
 // Rustの場合, 上限のある繰り返しは`for`または`while`を使います.
 // 分岐処理は`if`または`match`を使います.
 // あくまでこの記述は一例であり, 他にも様々な解き方があります.
+// 下記の別解は`src/strategy.rs`にもあり, `--strategy`で切り替えて`bench`で速度を比較できます.
+
+// 別解の見出しはコメントアウトした関数に付けた`///`のままにする
+#![allow(clippy::empty_line_after_doc_comments)]
 
 pub fn fizz_buzz(n: u64) {
     for i in 1..=n {
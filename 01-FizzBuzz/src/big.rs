@@ -0,0 +1,378 @@
+//! u64を越える任意桁数の数値のFizzBuzz
+//!
+//! 数値は10進数の文字列のまま保持し, 1つ進めるごとにその場で繰り上げます.
+//! 各ルールの除数での余りも増分の分だけ更新するため, 1つごとに多倍長の割り算を行う必要はありません.
+
+use std::{fmt, io, num::NonZeroU64, str::FromStr};
+
+use crate::{Config, Format, RecordWriter, WordRule};
+
+/// 任意桁数の非負整数
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    // 上位桁から順に並べたASCII数字. 先頭に余分な0は持たない
+    digits: Vec<u8>,
+}
+
+impl Decimal {
+    /// 累乗の形式 (`10^40`) で表せる数値の桁数の上限
+    pub const MAX_POW_DIGITS: usize = 100_000;
+
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.digits).expect("digits are always ASCII")
+    }
+
+    /// `divisor`で割った余り
+    pub fn rem(&self, divisor: u64) -> u64 {
+        let divisor = u128::from(divisor);
+        let rem = self
+            .digits
+            .iter()
+            .fold(0u128, |rem, &d| (rem * 10 + u128::from(d - b'0')) % divisor);
+
+        rem as u64
+    }
+
+    /// `k`をその場で加算する
+    pub fn add(&mut self, k: u64) {
+        let mut carry = k;
+        for d in self.digits.iter_mut().rev() {
+            if carry == 0 {
+                return;
+            }
+            let sum = u64::from(*d - b'0') + carry % 10;
+            carry /= 10;
+            if sum >= 10 {
+                *d = b'0' + (sum - 10) as u8;
+                carry += 1;
+            } else {
+                *d = b'0' + sum as u8;
+            }
+        }
+        if carry > 0 {
+            let head = carry.to_string().into_bytes();
+            self.digits.splice(0..0, head);
+        }
+    }
+
+    // 小さな数をその場で乗算する
+    fn mul(&mut self, m: u64) {
+        let mut carry = 0u128;
+        for d in self.digits.iter_mut().rev() {
+            let product = u128::from(*d - b'0') * u128::from(m) + carry;
+            *d = b'0' + (product % 10) as u8;
+            carry = product / 10;
+        }
+        if carry > 0 {
+            let head = carry.to_string().into_bytes();
+            self.digits.splice(0..0, head);
+        }
+        self.trim();
+    }
+
+    // 任意桁数の数値どうしの積. 9桁ずつに区切って筆算する
+    fn mul_decimal(&self, other: &Self) -> Self {
+        const LIMB: u64 = 1_000_000_000;
+        let limbs = |n: &Self| -> Vec<u64> {
+            n.digits
+                .rchunks(9)
+                .map(|chunk| {
+                    chunk
+                        .iter()
+                        .fold(0, |acc, &d| acc * 10 + u64::from(d - b'0'))
+                })
+                .collect()
+        };
+        let (lhs, rhs) = (limbs(self), limbs(other));
+
+        let mut product = vec![0u64; lhs.len() + rhs.len()];
+        for (i, &l) in lhs.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &r) in rhs.iter().enumerate() {
+                let t = product[i + j] + l * r + carry;
+                product[i + j] = t % LIMB;
+                carry = t / LIMB;
+            }
+            product[i + rhs.len()] = carry;
+        }
+
+        let digits: String = product
+            .iter()
+            .rev()
+            .map(|limb| format!("{:09}", limb))
+            .collect();
+        let mut n = Self {
+            digits: digits.into_bytes(),
+        };
+        n.trim();
+        n
+    }
+
+    // `base`の`exp`乗. 桁数が上限を越える場合は`None`を返す
+    fn pow(base: u64, exp: u32) -> Option<Self> {
+        match (base, exp) {
+            (_, 0) => return Some(Self::from(1)),
+            (0 | 1, _) => return Some(Self::from(base)),
+            _ => {}
+        }
+        // 桁数は floor(exp * log10(base)) + 1
+        if f64::from(exp) * (base as f64).log10() >= Self::MAX_POW_DIGITS as f64 {
+            return None;
+        }
+
+        // 10の累乗の因数は0を並べるだけにし, 残りを二乗と乗算の繰り返しで求める
+        let (mut m, mut zeros) = (base, 0);
+        while m % 10 == 0 {
+            m /= 10;
+            zeros += 1;
+        }
+        let mut n = Self::from(1);
+        if m > 1 {
+            for bit in (0..u32::BITS - exp.leading_zeros()).rev() {
+                n = n.mul_decimal(&n);
+                if exp >> bit & 1 == 1 {
+                    n.mul(m);
+                }
+            }
+        }
+        n.digits.resize(n.digits.len() + zeros * exp as usize, b'0');
+
+        Some(n)
+    }
+
+    // 任意桁数の数値を加算する
+    fn add_decimal(&mut self, other: &Self) {
+        let width = self.digits.len().max(other.digits.len());
+        let pad = |n: &Self| {
+            let mut digits = vec![b'0'; width - n.digits.len()];
+            digits.extend_from_slice(&n.digits);
+            digits
+        };
+        let (mut lhs, rhs) = (pad(self), pad(other));
+
+        let mut carry = 0;
+        for (l, r) in lhs.iter_mut().zip(&rhs).rev() {
+            let sum = (*l - b'0') + (r - b'0') + carry;
+            *l = b'0' + sum % 10;
+            carry = sum / 10;
+        }
+        if carry > 0 {
+            lhs.insert(0, b'1');
+        }
+        self.digits = lhs;
+    }
+
+    fn trim(&mut self) {
+        let zeros = self.digits.iter().take_while(|&&d| d == b'0').count();
+        let zeros = zeros.min(self.digits.len() - 1);
+        self.digits.drain(..zeros);
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        self.as_str().parse().ok()
+    }
+}
+
+impl From<u64> for Decimal {
+    fn from(n: u64) -> Self {
+        Self {
+            digits: n.to_string().into_bytes(),
+        }
+    }
+}
+
+/// `12345`のような10進数, または`10^40`のような累乗の形式を解析する
+impl FromStr for Decimal {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((base, exp)) = s.split_once('^') {
+            let base: u64 = base
+                .parse()
+                .map_err(|e| format!("invalid base `{}`: {}", base, e))?;
+            let exp: u32 = exp
+                .parse()
+                .map_err(|e| format!("invalid exponent `{}`: {}", exp, e))?;
+            return Self::pow(base, exp).ok_or_else(|| {
+                format!(
+                    "`{}` is too large (must have at most {} digits)",
+                    s,
+                    Self::MAX_POW_DIGITS
+                )
+            });
+        }
+
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("`{}` is not a non-negative decimal number", s));
+        }
+        let mut n = Self {
+            digits: s.as_bytes().to_vec(),
+        };
+        n.trim();
+
+        Ok(n)
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 入力された10進数の整数 (`--stdin`の各行, HTTPサーバの`/fizzbuzz/{n}`). i64に収まらない非負の整数も扱う
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Number {
+    Small(i64),
+    Big(Decimal),
+}
+
+impl Number {
+    /// 先頭の`+`を許して解析する. 整数でなければ`None`を返す
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        if let Ok(n) = s.parse() {
+            return Some(Self::Small(n));
+        }
+
+        // i64に収まらない非負の整数. 累乗の形式 (`10^40`) は受け付けない
+        let digits = s.strip_prefix('+').unwrap_or(s);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let n = digits.parse().expect("digits are always a valid decimal");
+        Some(Self::Big(n))
+    }
+
+    /// 設定のルールで判定した値
+    pub(crate) fn value(&self, config: &Config) -> Result<String, String> {
+        match self {
+            Self::Small(n) => Ok(config.value(*n)),
+            Self::Big(n) => config.value_big(n),
+        }
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Small(n) => write!(f, "{}", n),
+            Self::Big(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+/// 数値`n`に対し, 該当したルールの単語をすべて指定順に連結して返す
+///
+/// 割り切れるかで判定するルールは剰余で判定します. それ以外のルールは, u64に収まらない数値を判定できない場合にエラーとなります.
+pub fn classify_big(n: &Decimal, rules: &[WordRule]) -> Result<Option<String>, String> {
+    let mut word = String::new();
+    for rule in rules {
+        let matches = match rule.divisor() {
+            Some(divisor) => n.rem(divisor) == 0,
+            None => matches_big(rule, n)?,
+        };
+        if matches {
+            word += &rule.word;
+        }
+    }
+
+    Ok((!word.is_empty()).then_some(word))
+}
+
+// 除数を持たないルールの判定
+pub(crate) fn matches_big(rule: &WordRule, n: &Decimal) -> Result<bool, String> {
+    match n.to_u64() {
+        Some(n) => Ok(rule.rule.matches(n)),
+        None => rule
+            .rule
+            .matches_decimal(n.as_str())
+            .ok_or_else(|| format!("rule `{}` cannot be applied to {}", rule, n)),
+    }
+}
+
+/// `from`から`step`ずつ`count`個の数値を判定し, 指定の形式で書き出す
+pub fn write_big<W: io::Write>(
+    out: W,
+    from: Decimal,
+    step: NonZeroU64,
+    count: u64,
+    rules: &[WordRule],
+    format: Format,
+) -> io::Result<()> {
+    let words = rules.iter().map(|rule| rule.word.chars().count()).sum();
+
+    // 割り切れるかで判定するルールは, 各除数での余りを増分の分だけ更新していく
+    let mut residues: Option<Vec<Option<u64>>> = None;
+    write_big_with(out, from, step, count, format, words, |n, word| {
+        match &mut residues {
+            None => {
+                let init = rules.iter().map(|rule| rule.divisor().map(|d| n.rem(d)));
+                residues = Some(init.collect());
+            }
+            Some(residues) => {
+                for (rem, rule) in residues.iter_mut().zip(rules) {
+                    if let (Some(rem), Some(divisor)) = (rem, rule.divisor()) {
+                        *rem = ((u128::from(*rem) + u128::from(step.get())) % u128::from(divisor))
+                            as u64;
+                    }
+                }
+            }
+        }
+
+        for (rem, rule) in residues.iter().flatten().zip(rules) {
+            let matches = match rem {
+                Some(rem) => *rem == 0,
+                None => matches_big(rule, n)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+            };
+            if matches {
+                *word += &rule.word;
+            }
+        }
+        if word.is_empty() {
+            *word += n.as_str();
+        }
+        Ok(())
+    })
+}
+
+/// `from`から`step`ずつ`count`個の数値の値を`value`で求め, 指定の形式で書き出す
+///
+/// `value`は数値の昇順に1回ずつ, 空の文字列を渡して呼ばれます. `value_width`は表形式での値の列幅の目安です.
+pub fn write_big_with<W, F>(
+    out: W,
+    from: Decimal,
+    step: NonZeroU64,
+    count: u64,
+    format: Format,
+    value_width: usize,
+    mut value: F,
+) -> io::Result<()>
+where
+    W: io::Write,
+    F: FnMut(&Decimal, &mut String) -> io::Result<()>,
+{
+    let step = step.get();
+
+    // 表形式の列幅のため, 最後の値の桁数を求める
+    let mut last = from.clone();
+    if let Some(k) = count.checked_sub(1) {
+        let mut offset = Decimal::from(k);
+        offset.mul(step);
+        last.add_decimal(&offset);
+    }
+    let mut out = RecordWriter::with_width(out, format, last.as_str().len(), value_width)?;
+
+    let mut n = from;
+    let mut buf = String::new();
+    for i in 0..count {
+        if i > 0 {
+            n.add(step);
+        }
+        buf.clear();
+        value(&n, &mut buf)?;
+        out.write(&n, &buf)?;
+    }
+
+    out.finish()
+}
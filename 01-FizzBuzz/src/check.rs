@@ -0,0 +1,122 @@
+//! 解答として出力されたFizzBuzzの採点
+
+use std::{
+    fmt,
+    io::{self, BufRead},
+};
+
+/// 期待される出力との最初の相違点
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    /// 行の内容が異なる
+    Line {
+        line: u64,
+        expected: String,
+        actual: String,
+    },
+    /// 期待される行より先に出力が終わった
+    Missing { line: u64, expected: String },
+    /// 期待される行をすべて出力した後にも行がある
+    Extra { line: u64, actual: String },
+    /// 改行が`\r\n`になっている
+    CrLf { line: u64 },
+    /// 最終行が改行で終わっていない
+    NoTrailingNewline { line: u64 },
+}
+
+impl Mismatch {
+    /// 相違のある行番号 (1始まり)
+    pub fn line(&self) -> u64 {
+        match self {
+            Self::Line { line, .. }
+            | Self::Missing { line, .. }
+            | Self::Extra { line, .. }
+            | Self::CrLf { line }
+            | Self::NoTrailingNewline { line } => *line,
+        }
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Line {
+                line,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "line {}: expected {:?}, found {:?}",
+                line, expected, actual
+            ),
+            Self::Missing { line, expected } => {
+                write!(f, "line {}: output ended, expected {:?}", line, expected)
+            }
+            Self::Extra { line, actual } => {
+                write!(f, "line {}: unexpected extra line {:?}", line, actual)
+            }
+            Self::CrLf { line } => write!(
+                f,
+                "line {}: line ends with CRLF (\\r\\n), expected LF (\\n)",
+                line
+            ),
+            Self::NoTrailingNewline { line } => {
+                write!(f, "line {}: missing newline at end of output", line)
+            }
+        }
+    }
+}
+
+/// `input`を1行ずつ読み, `expected`と比較する
+///
+/// すべて一致した場合は比較した行数を, 相違があった場合は最初の相違点を返します.
+/// 入力は最初の相違点までしか読みません.
+pub fn check<R, I>(mut input: R, expected: I) -> io::Result<Result<u64, Mismatch>>
+where
+    R: BufRead,
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut expected = expected.into_iter();
+    let mut buf = Vec::new();
+    let mut line = 0;
+
+    loop {
+        buf.clear();
+        let read = input.read_until(b'\n', &mut buf)?;
+        let next = expected.next();
+        line += 1;
+
+        let (expected, actual) = match (next, read) {
+            (None, 0) => return Ok(Ok(line - 1)),
+            (Some(expected), 0) => {
+                let expected = expected.as_ref().to_string();
+                return Ok(Err(Mismatch::Missing { line, expected }));
+            }
+            (None, _) => {
+                let actual = String::from_utf8_lossy(trim_newline(&buf)).into_owned();
+                return Ok(Err(Mismatch::Extra { line, actual }));
+            }
+            (Some(expected), _) => (expected, trim_newline(&buf)),
+        };
+
+        if actual != expected.as_ref().as_bytes() {
+            return Ok(Err(Mismatch::Line {
+                line,
+                expected: expected.as_ref().to_string(),
+                actual: String::from_utf8_lossy(actual).into_owned(),
+            }));
+        }
+        if buf.ends_with(b"\r\n") {
+            return Ok(Err(Mismatch::CrLf { line }));
+        }
+        if !buf.ends_with(b"\n") {
+            return Ok(Err(Mismatch::NoTrailingNewline { line }));
+        }
+    }
+}
+
+fn trim_newline(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
@@ -0,0 +1,56 @@
+//! ANSIエスケープシーケンスによる単語の色付け (`--color auto|always|never`)
+
+use std::{
+    env,
+    io::{self, IsTerminal},
+};
+
+use crate::names::named;
+
+/// ルールの順に割り当てる前景色 (黄, シアン, マゼンタ, 緑, 青, 赤). 7つ目以降のルールは先頭から繰り返す
+const PALETTE: [u8; 6] = [33, 36, 35, 32, 34, 31];
+
+/// 色付けするか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// 出力先が端末で, 環境変数`NO_COLOR`が空でない値に設定されていない場合のみ (既定)
+    #[default]
+    Auto,
+    /// 常に色付けする
+    Always,
+    /// 色付けしない
+    Never,
+}
+
+named!(ColorChoice, "color", {
+    Auto => "auto",
+    Always => "always",
+    Never => "never",
+});
+
+impl ColorChoice {
+    /// 出力先が端末か (`is_terminal`), `NO_COLOR`が設定されているか (`no_color`) から, 色付けするかを決める
+    pub fn enabled(self, is_terminal: bool, no_color: bool) -> bool {
+        match self {
+            Self::Auto => is_terminal && !no_color,
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+
+    /// 標準出力に書き出す場合に色付けするか
+    pub fn enabled_for_stdout(self) -> bool {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        self.enabled(io::stdout().is_terminal(), no_color)
+    }
+}
+
+// `index`番目のルールの単語を色付けして追加する. 複数のルールに該当した単語は太字にする
+pub(crate) fn paint(s: &mut String, word: &str, index: usize, bold: bool) {
+    let color = PALETTE[index % PALETTE.len()];
+    if bold {
+        *s += &format!("\x1b[1;{}m{}\x1b[0m", color, word);
+    } else {
+        *s += &format!("\x1b[{}m{}\x1b[0m", color, word);
+    }
+}
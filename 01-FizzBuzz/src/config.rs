@@ -0,0 +1,300 @@
+//! ルール定義の設定ファイル (TOML, JSON)
+//!
+//! ```toml
+//! separator = "-"     # 単語の区切り (既定: 区切りなし)
+//! fallback = "({n})"  # どのルールにも該当しない場合の値. {n}は数値に置き換えられる (既定: 数値)
+//!
+//! [[rules]]
+//! divisor = 3
+//! word = "Fizz"
+//!
+//! [[rules]]
+//! kind = "contains"
+//! digit = 7
+//! word = "Lucky"
+//! ```
+//!
+//! ルールは記述した順に判定し, 該当した単語を区切りで連結します.
+//! `kind`は`divisible` (既定, `divisor`で割り切れる), `contains` (`digit`を含む), `prime` (素数) のいずれかです.
+
+use std::{collections::HashMap, fmt, fs, io, path::Path};
+
+use serde::Deserialize;
+
+use crate::{
+    big::matches_big, color::paint, ContainsDigit, Decimal, DivisibleBy, Integer, IsPrime,
+    Notation, WordRule,
+};
+
+/// 作業ディレクトリから自動的に読み込む設定ファイル名
+pub const DEFAULT_CONFIG: &str = "fizzbuzz.toml";
+
+/// ルールと, 単語の連結方法の設定
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub rules: Vec<WordRule>,
+    /// 該当した単語どうしの区切り
+    pub separator: String,
+    /// どのルールにも該当しない場合の値. `{n}`は数値に置き換えられる
+    pub fallback: Option<String>,
+    /// どのルールにも該当しない数値の表記. u64を越える数値は常に10進数で表す
+    pub notation: Notation,
+    /// 単語をANSIエスケープシーケンスでルールごとに色付けするか. 複数のルールに該当した単語は太字にする
+    pub color: bool,
+}
+
+impl Default for Config {
+    /// 通常のFizzBuzz
+    fn default() -> Self {
+        Self::new(WordRule::classic())
+    }
+}
+
+impl Config {
+    /// 区切りなし, 該当しない場合は数値を出力する設定
+    pub fn new(rules: Vec<WordRule>) -> Self {
+        Self {
+            rules,
+            separator: String::new(),
+            fallback: None,
+            notation: Notation::Decimal,
+            color: false,
+        }
+    }
+
+    /// ファイルを読み込む. 拡張子が`.json`の場合はJSON, それ以外はTOMLとして解析します
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(ConfigError::Io)?;
+        if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_json(&text)
+        } else {
+            Self::from_toml(&text)
+        }
+    }
+
+    pub fn from_toml(s: &str) -> Result<Self, ConfigError> {
+        let file: File = toml::from_str(s).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        file.validate()
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, ConfigError> {
+        let file: File = serde_json::from_str(s).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        file.validate()
+    }
+
+    /// 区切りも置き換えもなく, ルールの単語と10進数の数値をそのまま出力するだけの設定か
+    ///
+    /// この場合は[`write_with_rules`](crate::write_with_rules)などの高速な経路で出力できます.
+    pub fn is_plain(&self) -> bool {
+        self.separator.is_empty()
+            && self.fallback.is_none()
+            && self.notation == Notation::Decimal
+            && !self.color
+    }
+
+    /// 数値`n`に対して出力する値
+    pub fn value<N: Integer>(&self, n: N) -> String {
+        let words = self.rules.iter().enumerate();
+        let words = words.filter(|(_, rule)| rule.matches(n));
+        self.join(words.map(|(i, rule)| (i, rule.word.as_str())), || {
+            self.notation.format(n)
+        })
+    }
+
+    /// u64を越える数値`n`に対して出力する値
+    pub fn value_big(&self, n: &Decimal) -> Result<String, String> {
+        let mut words = Vec::new();
+        for (i, rule) in self.rules.iter().enumerate() {
+            let matches = match rule.divisor() {
+                Some(divisor) => n.rem(divisor) == 0,
+                None => matches_big(rule, n)?,
+            };
+            if matches {
+                words.push((i, rule.word.as_str()));
+            }
+        }
+
+        Ok(self.join(words.into_iter(), || match n.to_u64() {
+            Some(n) => self.notation.format(n),
+            None => n.to_string(),
+        }))
+    }
+
+    /// 表形式での値の列幅の目安
+    pub fn value_width(&self) -> usize {
+        let words: usize = self
+            .rules
+            .iter()
+            .map(|rule| rule.word.chars().count())
+            .sum();
+        let separators = self.separator.chars().count() * self.rules.len().saturating_sub(1);
+        let fallback = self.fallback.as_ref().map_or(0, |s| s.chars().count());
+        (words + separators).max(fallback)
+    }
+
+    // 該当したルールの番号と単語を連結する. 該当しない場合は数値 (または置き換えの値) を返す
+    fn join<'a>(
+        &self,
+        words: impl Iterator<Item = (usize, &'a str)>,
+        number: impl FnOnce() -> String,
+    ) -> String {
+        let mut words = words.peekable();
+        let Some((i, first)) = words.next() else {
+            return match &self.fallback {
+                Some(fallback) => fallback.replace("{n}", &number()),
+                None => number(),
+            };
+        };
+
+        let bold = words.peek().is_some();
+        let mut s = String::new();
+        let push = |s: &mut String, i, word| {
+            if self.color {
+                paint(s, word, i, bold);
+            } else {
+                *s += word;
+            }
+        };
+        push(&mut s, i, first);
+        for (i, word) in words {
+            s += &self.separator;
+            push(&mut s, i, word);
+        }
+        s
+    }
+}
+
+/// 設定ファイルの読み込みエラー
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    /// TOML, JSONとして解析できない
+    Parse(String),
+    /// ルールの定義が不正. `index`は1から数えた位置
+    Rule {
+        index: usize,
+        word: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::Parse(message) => write!(f, "{}", message.trim_end()),
+            Self::Rule {
+                index,
+                word,
+                message,
+            } => write!(f, "rule #{} (word = {:?}): {}", index, word, message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// ファイルの内容そのもの
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct File {
+    #[serde(default)]
+    separator: String,
+    fallback: Option<String>,
+    #[serde(default)]
+    rules: Vec<Entry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    kind: Option<String>,
+    divisor: Option<u64>,
+    digit: Option<u8>,
+    word: String,
+}
+
+impl File {
+    fn validate(self) -> Result<Config, ConfigError> {
+        // ルールの指定がない場合は通常のFizzBuzz
+        let rules = if self.rules.is_empty() {
+            WordRule::classic()
+        } else {
+            let mut seen = HashMap::new();
+            let mut rules = Vec::with_capacity(self.rules.len());
+            for (i, entry) in self.rules.into_iter().enumerate() {
+                let index = i + 1;
+                let error = |message: String| ConfigError::Rule {
+                    index,
+                    word: entry.word.clone(),
+                    message,
+                };
+
+                if entry.word.is_empty() {
+                    return Err(error("word must not be empty".into()));
+                }
+                if let Some(first) = seen.insert(entry.word.clone(), index) {
+                    return Err(error(format!(
+                        "duplicate word (already used by rule #{})",
+                        first
+                    )));
+                }
+                rules.push(entry.rule().map_err(error)?);
+            }
+            rules
+        };
+
+        Ok(Config {
+            rules,
+            separator: self.separator,
+            fallback: self.fallback,
+            notation: Notation::Decimal,
+            color: false,
+        })
+    }
+}
+
+impl Entry {
+    fn rule(&self) -> Result<WordRule, String> {
+        let word = self.word.as_str();
+        let kind = self.kind.as_deref().unwrap_or("divisible");
+        let unused = |field: &str| format!("`{}` cannot be used with kind `{}`", field, kind);
+
+        match kind {
+            "divisible" => {
+                if self.digit.is_some() {
+                    return Err(unused("digit"));
+                }
+                match self.divisor {
+                    None => Err("missing `divisor`".into()),
+                    Some(0) => Err("divisor must be greater than 0".into()),
+                    Some(divisor) => Ok(WordRule::new(DivisibleBy(divisor), word)),
+                }
+            }
+            "contains" => {
+                if self.divisor.is_some() {
+                    return Err(unused("divisor"));
+                }
+                match self.digit {
+                    None => Err("missing `digit`".into()),
+                    Some(digit @ 0..=9) => Ok(WordRule::new(ContainsDigit(digit), word)),
+                    Some(digit) => Err(format!("invalid digit {}: expected 0-9", digit)),
+                }
+            }
+            "prime" => {
+                if self.divisor.is_some() {
+                    return Err(unused("divisor"));
+                }
+                if self.digit.is_some() {
+                    return Err(unused("digit"));
+                }
+                Ok(WordRule::new(IsPrime, word))
+            }
+            _ => Err(format!(
+                "unknown rule kind `{}` (expected divisible, contains or prime)",
+                kind
+            )),
+        }
+    }
+}
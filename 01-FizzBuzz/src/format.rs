@@ -0,0 +1,219 @@
+//! 構造化された出力形式 (JSON, NDJSON, CSV, 表)
+
+use std::{
+    fmt,
+    io::{self, BufWriter, Write},
+};
+
+use crate::{classify, names::named, Integer, WordRule, BUFFER_SIZE};
+
+/// 出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// 1行に1つの値のみ (既定)
+    #[default]
+    Text,
+    /// `{"n":15,"value":"FizzBuzz"}` の配列
+    Json,
+    /// 1行に1つのJSONオブジェクト
+    Ndjson,
+    /// ヘッダ付きのCSV
+    Csv,
+    /// 桁を揃えた表
+    Table,
+}
+
+named!(Format, "format", {
+    Text => "text",
+    Json => "json",
+    Ndjson => "ndjson",
+    Csv => "csv",
+    Table => "table",
+});
+
+/// 数値の列を任意のルールで判定し, 指定の形式で書き出す
+///
+/// 表形式の列幅は先頭と末尾の値から決めるため, `values`は単調に増加または減少する列を想定しています.
+pub fn write_formatted<W, I>(
+    out: W,
+    values: I,
+    rules: &[WordRule],
+    format: Format,
+) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator,
+    I::IntoIter: DoubleEndedIterator + Clone,
+    I::Item: Integer,
+{
+    write_values(out, values, format, words_width(rules), |n| {
+        classify(n, rules).unwrap_or_else(|| n.to_string())
+    })
+}
+
+/// 各数値の値を`value`で求め, 指定の形式で書き出す
+///
+/// `value_width`は表形式での値の列幅の目安です. (数値の桁数の方が大きい場合はそちらを使います)
+pub fn write_values<W, I, F>(
+    out: W,
+    values: I,
+    format: Format,
+    value_width: usize,
+    mut value: F,
+) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator,
+    I::IntoIter: DoubleEndedIterator + Clone,
+    I::Item: Integer,
+    F: FnMut(I::Item) -> String,
+{
+    let values = values.into_iter();
+    let mut ends = values.clone();
+    let width = |n: Option<I::Item>| n.map_or(1, |n| n.to_string().len());
+    let n_width = width(ends.next()).max(width(ends.next_back()));
+
+    let mut out = RecordWriter::with_width(out, format, n_width, value_width)?;
+    for n in values {
+        out.write(n, &value(n))?;
+    }
+
+    out.finish()
+}
+
+// すべての単語が連結された場合の幅
+fn words_width(rules: &[WordRule]) -> usize {
+    rules.iter().map(|rule| rule.word.chars().count()).sum()
+}
+
+/// 数値と値の組を1件ずつ, 指定の形式で書き出す
+pub struct RecordWriter<W: Write> {
+    out: BufWriter<W>,
+    format: Format,
+    n_width: usize,
+    value_width: usize,
+    empty: bool,
+}
+
+impl<W: Write> RecordWriter<W> {
+    /// 必要に応じてヘッダを書き出す
+    ///
+    /// `n_width`は表形式での数値の列幅です. 値の列はすべての単語が連結された場合でも収まる幅にします.
+    pub fn new(out: W, format: Format, n_width: usize, rules: &[WordRule]) -> io::Result<Self> {
+        Self::with_width(out, format, n_width, words_width(rules))
+    }
+
+    /// 値の列幅を直接指定する. 数値の列幅より小さい場合は数値の列幅を使います
+    pub fn with_width(
+        out: W,
+        format: Format,
+        n_width: usize,
+        value_width: usize,
+    ) -> io::Result<Self> {
+        let value_width = n_width.max(value_width).max("value".len());
+        let mut out = BufWriter::with_capacity(BUFFER_SIZE, out);
+
+        match format {
+            Format::Json => out.write_all(b"[")?,
+            Format::Csv => out.write_all(b"n,value\n")?,
+            Format::Table => {
+                writeln!(
+                    out,
+                    "| {:>w$} | {:<v$} |",
+                    "n",
+                    "value",
+                    w = n_width,
+                    v = value_width
+                )?;
+                writeln!(
+                    out,
+                    "|{:->w$}:|:{:-<v$}|",
+                    "",
+                    "",
+                    w = n_width + 1,
+                    v = value_width + 1
+                )?;
+            }
+            Format::Text | Format::Ndjson => {}
+        }
+
+        Ok(Self {
+            out,
+            format,
+            n_width,
+            value_width,
+            empty: true,
+        })
+    }
+
+    pub fn write<N: fmt::Display>(&mut self, n: N, value: &str) -> io::Result<()> {
+        let out = &mut self.out;
+        match self.format {
+            Format::Text => writeln!(out, "{}", value)?,
+            Format::Json => {
+                out.write_all(if self.empty { b"\n" } else { b",\n" })?;
+                write!(out, "{{\"n\":{},\"value\":{}}}", n, Json(value))?;
+            }
+            Format::Ndjson => writeln!(out, "{{\"n\":{},\"value\":{}}}", n, Json(value))?,
+            Format::Csv => writeln!(out, "{},{}", n, Csv(value))?,
+            Format::Table => {
+                let pad = self.value_width.saturating_sub(value.chars().count());
+                writeln!(
+                    out,
+                    "| {:>w$} | {}{:pad$} |",
+                    n,
+                    value,
+                    "",
+                    w = self.n_width,
+                    pad = pad
+                )?;
+            }
+        }
+        self.empty = false;
+
+        Ok(())
+    }
+
+    /// 必要に応じてフッタを書き出し, フラッシュする
+    pub fn finish(mut self) -> io::Result<()> {
+        if self.format == Format::Json {
+            let footer: &[u8] = if self.empty { b"]\n" } else { b"\n]\n" };
+            self.out.write_all(footer)?;
+        }
+
+        self.out.flush()
+    }
+}
+
+/// JSONの文字列リテラルとして出力する
+struct Json<'a>(&'a str);
+
+impl fmt::Display for Json<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("\"")?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+        f.write_str("\"")
+    }
+}
+
+/// CSVのフィールドとして出力する. 区切り文字などを含む場合のみ引用符で囲む
+pub(crate) struct Csv<'a>(pub(crate) &'a str);
+
+impl fmt::Display for Csv<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.0.contains([',', '"', '\n', '\r']) {
+            return f.write_str(self.0);
+        }
+        write!(f, "\"{}\"", self.0.replace('"', "\"\""))
+    }
+}
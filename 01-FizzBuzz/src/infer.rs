@@ -0,0 +1,490 @@
+//! FizzBuzz風の出力から, それを生成した除数と単語のルールを推定する処理
+//!
+//! 各行は連続する数値に対応するものとし, 開始値は数値の行から求めます. (数値の行がなければ1から)
+//! 単語の行は, 他の単語の連結として表せない単語 (`FizzBuzz`に対する`Fizz`と`Buzz`) に分解し,
+//! 各単語が現れる数値の間隔から除数を求めます. 推定したルールは生成と同じ[`WordRule`]なので,
+//! そのまま出力を再生成して確かめられます.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    io::{self, BufRead},
+};
+
+use crate::{query::gcd, DivisibleBy, WordRule};
+
+/// 1つの単語の除数の候補を探す回数の上限
+const MAX_CANDIDATES: u64 = 1_000_000;
+
+/// 推定の結果
+#[derive(Debug, Clone)]
+pub struct Inference {
+    /// 推定したルール (判定する順)
+    pub rules: Vec<WordRule>,
+    /// 1行目に対応する数値
+    pub start: u64,
+    /// 行数
+    pub lines: u64,
+    /// 曖昧な点と矛盾
+    pub issues: Vec<Issue>,
+}
+
+impl Inference {
+    /// 矛盾がないか (曖昧な点はあってもよい)
+    pub fn is_consistent(&self) -> bool {
+        !self.issues.iter().any(Issue::is_contradiction)
+    }
+
+    /// 推定したルールを`--config`で読み込める設定ファイル (TOML) として返す
+    pub fn to_toml(&self) -> String {
+        let mut s = match self.lines {
+            0 => "# inferred from 0 lines\n".to_string(),
+            lines => format!(
+                "# inferred from {} lines ({}..={})\n",
+                lines,
+                self.start,
+                self.start + (lines - 1)
+            ),
+        };
+        for rule in &self.rules {
+            let divisor = rule.divisor().expect("inferred rules are divisor rules");
+            s += &format!(
+                "\n[[rules]]\ndivisor = {}\nword = {}\n",
+                divisor,
+                toml::Value::String(rule.word.clone())
+            );
+        }
+        s
+    }
+}
+
+/// 推定できなかった, または一意に定まらなかった点
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// 数値の行が, 開始値から数えた数値と異なる
+    NumberMismatch {
+        line: u64,
+        expected: u64,
+        found: String,
+    },
+    /// 空行
+    EmptyLine { line: u64 },
+    /// 単語を複数の方法で分解できる (最初の分解を使う)
+    AmbiguousSplit {
+        line: u64,
+        word: String,
+        splits: [Vec<String>; 2],
+    },
+    /// 単語が1回しか現れず, 除数が一意に定まらない (最小の除数を使う)
+    AmbiguousDivisor {
+        word: String,
+        n: u64,
+        divisors: Vec<u64>,
+    },
+    /// 除数の倍数なのに単語が現れない
+    Unexplained {
+        line: u64,
+        word: String,
+        divisor: u64,
+        found: String,
+    },
+    /// 単語の現れる数値が, どの除数の倍数とも一致しない
+    NoDivisor { word: String, numbers: Vec<u64> },
+    /// 単語の順序が他の行と逆になっている
+    Order {
+        line: u64,
+        first: String,
+        second: String,
+    },
+}
+
+impl Issue {
+    /// 矛盾か (曖昧なだけではないか)
+    pub fn is_contradiction(&self) -> bool {
+        !matches!(
+            self,
+            Self::AmbiguousSplit { .. } | Self::AmbiguousDivisor { .. }
+        )
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NumberMismatch {
+                line,
+                expected,
+                found,
+            } => write!(f, "line {}: expected {}, found {:?}", line, expected, found),
+            Self::EmptyLine { line } => write!(f, "line {}: empty line", line),
+            Self::AmbiguousSplit {
+                line,
+                word,
+                splits: [a, b],
+            } => write!(
+                f,
+                "line {}: {:?} can be split in more than one way ({} or {})",
+                line,
+                word,
+                a.join("+"),
+                b.join("+")
+            ),
+            Self::AmbiguousDivisor { word, n, divisors } => {
+                let list: Vec<_> = divisors.iter().map(u64::to_string).collect();
+                write!(
+                    f,
+                    "{:?} appears only at {}, so its divisor could be any of {} (using {})",
+                    word,
+                    n,
+                    list.join(", "),
+                    divisors[0]
+                )
+            }
+            Self::Unexplained {
+                line,
+                word,
+                divisor,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {:?} (multiple of {}), found {:?}",
+                line, word, divisor, found
+            ),
+            Self::NoDivisor { word, numbers } => {
+                let mut list: Vec<_> = numbers.iter().take(5).map(u64::to_string).collect();
+                if numbers.len() > 5 {
+                    list.push("...".to_string());
+                }
+                write!(
+                    f,
+                    "{:?} appears at {}, which are not the multiples of any divisor",
+                    word,
+                    list.join(", ")
+                )
+            }
+            Self::Order {
+                line,
+                first,
+                second,
+            } => write!(
+                f,
+                "line {}: {:?} comes before {:?}, contradicting the order on other lines",
+                line, first, second
+            ),
+        }
+    }
+}
+
+/// `input`を1行ずつ読み, 各行を生成したルールを推定する
+///
+/// `start`を指定した場合は, 1行目をその数値とします.
+/// 単語の分解から得た単語がどの行にも使われず, ルールを推定できない場合は`InvalidData`のエラーを返します.
+pub fn infer<R: BufRead>(input: R, start: Option<u64>) -> io::Result<Inference> {
+    let lines = input
+        .lines()
+        .map(|line| line.map(|line| line.trim().to_string()))
+        .collect::<io::Result<Vec<_>>>()?;
+    let mut issues = Vec::new();
+
+    // 開始値は, 1行目まで遡れる最初の数値の行から求める
+    let start = start
+        .or_else(|| {
+            lines
+                .iter()
+                .enumerate()
+                .find_map(|(i, line)| line.parse::<u64>().ok()?.checked_sub(i as u64))
+        })
+        .unwrap_or(1);
+    let number = |i: usize| start.saturating_add(i as u64);
+    let end = number(lines.len().saturating_sub(1));
+
+    let mut words = Vec::new();
+    let mut seen = HashSet::new();
+    for (i, line) in lines.iter().enumerate() {
+        if line.is_empty() {
+            issues.push(Issue::EmptyLine { line: i as u64 + 1 });
+        } else if is_integer(line) {
+            if line.parse::<u64>().ok() != Some(number(i)) {
+                issues.push(Issue::NumberMismatch {
+                    line: i as u64 + 1,
+                    expected: number(i),
+                    found: line.clone(),
+                });
+            }
+        } else if seen.insert(line.as_str()) {
+            words.push(line.clone());
+        }
+    }
+    let atoms = atoms(words, &lines, number);
+    let atoms: Vec<&str> = atoms.iter().map(String::as_str).collect();
+
+    // 各単語が現れる数値と, 行内での単語の順序
+    let mut numbers = vec![Vec::new(); atoms.len()];
+    let mut before = HashMap::new();
+    let mut ambiguous = HashSet::new();
+    for (i, line) in lines.iter().enumerate() {
+        if line.is_empty() || is_integer(line) {
+            continue;
+        }
+        let splits = split(line, &atoms, 2);
+        // 曖昧な分解は, 単語ごとに最初の行のみ示す
+        if let ([a, b], true) = (&splits[..], ambiguous.insert(line.as_str())) {
+            let names = |parts: &[usize]| parts.iter().map(|&j| atoms[j].to_string()).collect();
+            issues.push(Issue::AmbiguousSplit {
+                line: i as u64 + 1,
+                word: line.clone(),
+                splits: [names(a), names(b)],
+            });
+        }
+        let parts = &splits[0];
+        for (k, &a) in parts.iter().enumerate() {
+            numbers[a].push(number(i));
+            for &b in &parts[k + 1..] {
+                before.entry((a, b)).or_insert(i as u64 + 1);
+            }
+        }
+    }
+
+    let mut divisors = Vec::with_capacity(atoms.len());
+    for (&word, numbers) in atoms.iter().zip(&numbers) {
+        // 他の単語の分解から得た単語が, どの行の分解にも使われなかった場合
+        if numbers.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "cannot infer the rules: \"{}\" does not appear in any line",
+                    word
+                ),
+            ));
+        }
+        let (divisor, issue) = divisor(word, numbers, start, end, &lines);
+        divisors.push(divisor);
+        issues.extend(issue);
+    }
+
+    // 行内の順序に従い, 順序の定まらない単語どうしは除数の小さい順に並べる
+    let mut order = Vec::with_capacity(atoms.len());
+    let mut remaining: Vec<usize> = (0..atoms.len())
+        .filter(|&a| divisors[a].is_some())
+        .collect();
+    remaining.sort_by_key(|&a| divisors[a]);
+    while !remaining.is_empty() {
+        let next = remaining
+            .iter()
+            .position(|&b| {
+                !remaining
+                    .iter()
+                    .any(|&a| a != b && before.contains_key(&(a, b)))
+            })
+            // 順序が循環している場合は, 除数の小さい順で続ける
+            .unwrap_or(0);
+        order.push(remaining.remove(next));
+    }
+
+    let position: HashMap<_, _> = order.iter().enumerate().map(|(i, &a)| (a, i)).collect();
+    let mut reversed: Vec<_> = before
+        .iter()
+        .filter(|(&(a, b), _)| match (position.get(&a), position.get(&b)) {
+            (Some(a), Some(b)) => a > b,
+            _ => false,
+        })
+        .map(|(&(a, b), &line)| (line, a, b))
+        .collect();
+    reversed.sort_unstable();
+    issues.extend(reversed.into_iter().map(|(line, a, b)| Issue::Order {
+        line,
+        first: atoms[a].to_string(),
+        second: atoms[b].to_string(),
+    }));
+
+    let rules = order
+        .into_iter()
+        .map(|a| {
+            let divisor = divisors[a].expect("only atoms with a divisor are ordered");
+            WordRule::new(DivisibleBy(divisor), atoms[a])
+        })
+        .collect();
+
+    Ok(Inference {
+        rules,
+        start,
+        lines: lines.len() as u64,
+        issues,
+    })
+}
+
+// 単語を, 短い順に他の単語の連結として表せないものから基本の単語とする
+//
+// 基本の単語が他の基本の単語と未知の1語の連結として表せる場合 (`EvenFour`に対する`Even`と`Four`) は,
+// その1語も単語に加えて求め直す. ただし連結される既知の単語は, 連結された単語の現れる数値でも現れうるものに限る
+fn atoms(mut words: Vec<String>, lines: &[String], number: impl Fn(usize) -> u64) -> Vec<String> {
+    loop {
+        words.sort_by_key(String::len);
+        let mut atoms: Vec<&str> = Vec::new();
+        for word in &words {
+            if split(word, &atoms, 1).is_empty() {
+                atoms.push(word);
+            }
+        }
+
+        let mut numbers = vec![Vec::new(); atoms.len()];
+        for (i, line) in lines.iter().enumerate() {
+            if line.is_empty() || is_integer(line) {
+                continue;
+            }
+            for &a in &split(line, &atoms, 1)[0] {
+                numbers[a].push(number(i));
+            }
+        }
+        let gcds: Vec<u64> = numbers
+            .iter()
+            .map(|numbers| numbers.iter().fold(0, |g, &n| gcd(g, n)))
+            .collect();
+
+        let residual = atoms.iter().enumerate().find_map(|(i, word)| {
+            let others: Vec<_> = (0..atoms.len()).filter(|&j| j != i).collect();
+            let names: Vec<_> = others.iter().map(|&j| atoms[j]).collect();
+            let explains = |parts: &[usize]| {
+                parts.iter().all(|&k| {
+                    let g = gcds[others[k]];
+                    numbers[i].iter().all(|n| n.is_multiple_of(g))
+                })
+            };
+
+            let bounds: Vec<_> = word
+                .char_indices()
+                .map(|(k, _)| k)
+                .chain([word.len()])
+                .collect();
+            bounds.iter().enumerate().find_map(|(x, &from)| {
+                bounds[x + 1..].iter().find_map(|&to| {
+                    let rest = &word[from..to];
+                    if rest.len() == word.len() || words.iter().any(|w| w == rest) {
+                        return None;
+                    }
+                    let prefix = split(&word[..from], &names, 1).pop()?;
+                    let suffix = split(&word[to..], &names, 1).pop()?;
+                    let disjoint = !prefix.iter().any(|k| suffix.contains(k));
+                    (disjoint && explains(&prefix) && explains(&suffix)).then(|| rest.to_string())
+                })
+            })
+        });
+
+        match residual {
+            Some(residual) => words.push(residual),
+            None => return atoms.into_iter().map(str::to_string).collect(),
+        }
+    }
+}
+
+fn is_integer(s: &str) -> bool {
+    s.parse::<i128>().is_ok() || s.bytes().all(|b| b.is_ascii_digit())
+}
+
+// `word`を`atoms`の重複のない連結に分解する方法を, `limit`個まで返す
+fn split(word: &str, atoms: &[&str], limit: usize) -> Vec<Vec<usize>> {
+    fn go(
+        word: &str,
+        atoms: &[&str],
+        used: &mut Vec<usize>,
+        found: &mut Vec<Vec<usize>>,
+        limit: usize,
+    ) {
+        if word.is_empty() {
+            found.push(used.clone());
+            return;
+        }
+        for (i, atom) in atoms.iter().enumerate() {
+            if found.len() >= limit {
+                return;
+            }
+            if let Some(rest) = word.strip_prefix(atom).filter(|_| !used.contains(&i)) {
+                used.push(i);
+                go(rest, atoms, used, found, limit);
+                used.pop();
+            }
+        }
+    }
+
+    // 基本の単語そのものは, それ以上分解しない
+    if let Some(i) = atoms.iter().position(|&atom| atom == word) {
+        return vec![vec![i]];
+    }
+    let mut found = Vec::new();
+    go(word, atoms, &mut Vec::new(), &mut found, limit);
+    found
+}
+
+// 単語が現れる数値 (昇順, 1つ以上) から除数を求める
+fn divisor(
+    word: &str,
+    numbers: &[u64],
+    start: u64,
+    end: u64,
+    lines: &[String],
+) -> (Option<u64>, Option<Issue>) {
+    // 2回以上現れる場合, 除数はそれらの最大公約数 (隣り合う倍数の差) に限られる
+    if let [_, _, ..] = numbers {
+        let divisor = numbers.iter().fold(0, |g, &n| gcd(g, n));
+        let first = start.div_ceil(divisor) * divisor;
+        let missing = (first..=end)
+            .step_by(divisor as usize)
+            .find(|n| numbers.binary_search(n).is_err());
+        return match missing {
+            None => (Some(divisor), None),
+            Some(_) if divisor == 1 => (
+                None,
+                Some(Issue::NoDivisor {
+                    word: word.to_string(),
+                    numbers: numbers.to_vec(),
+                }),
+            ),
+            Some(n) => (
+                Some(divisor),
+                Some(Issue::Unexplained {
+                    line: n - start + 1,
+                    word: word.to_string(),
+                    divisor,
+                    found: lines[(n - start) as usize].clone(),
+                }),
+            ),
+        };
+    }
+
+    // 1回しか現れない場合は, 範囲内の倍数がその数値のみとなる約数が候補になる
+    let n = numbers[0];
+    let lower = (n - start).max(end - n);
+    let mut candidates = Vec::new();
+    if n == 0 {
+        candidates.extend([lower + 1, lower + 2]);
+    } else {
+        let max = n / (lower + 1);
+        for k in (max.saturating_sub(MAX_CANDIDATES)..=max).rev() {
+            if k == 0 || candidates.len() == 3 {
+                break;
+            }
+            if n.is_multiple_of(k) {
+                candidates.push(n / k);
+            }
+        }
+    }
+
+    match candidates[..] {
+        [] => (
+            None,
+            Some(Issue::NoDivisor {
+                word: word.to_string(),
+                numbers: numbers.to_vec(),
+            }),
+        ),
+        [divisor] => (Some(divisor), None),
+        [divisor, ..] => (
+            Some(divisor),
+            Some(Issue::AmbiguousDivisor {
+                word: word.to_string(),
+                n,
+                divisors: candidates,
+            }),
+        ),
+    }
+}
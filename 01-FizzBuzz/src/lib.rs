@@ -0,0 +1,88 @@
+//! # FizzBuzz
+//!
+//! FizzBuzzの判定処理をライブラリとして提供します.
+//! 標準ライブラリを使わない判定と整形の処理は`fizz-buzz-core`クレートにあり, ここから再公開しています.
+//!
+//! - [`FizzBuzzValue`] : 1つの数値に対するFizzBuzz値. 文字列 ([`ParseValueError`]) やJSON (serde) と相互に変換できる
+//! - [`FizzBuzz`] : 指定範囲のFizzBuzz値を順に返すイテレータ
+//! - [`fizz_buzz_value`], [`FizzBuzzTable`] : コンパイル時に評価できるFizzBuzz値と, その表
+//! - [`Lines`], [`write_lines`], [`SliceWriter`] : ヒープを使わず, 呼び出し側のバッファや[`core::fmt::Write`]へ書き込む処理
+//! - [`Rule`] : 単語を出力する条件 ([`DivisibleBy`], [`ContainsDigit`], [`IsPrime`], [`Custom`])
+//! - [`WordRule`] : ルールと単語の組
+//! - [`Notation`] : どのルールにも該当しない数値の表記 (N進数, ローマ数字, 漢数字)
+//! - [`ColorChoice`] : 端末への出力での単語の色付け
+//! - [`Locale`] : 組み込みの単語の組 (英語, 日本語, ドイツ語)
+//! - [`Config`] : 設定ファイル (TOML, JSON) によるルールと単語の連結方法の定義
+//! - [`Span`] : 負の数や降順を含む任意の範囲
+//! - [`Decimal`] : u64を越える任意桁数の数値
+//! - [`write_fizz_buzz`] : 大量の出力を高速に書き出す処理
+//! - [`Strategy`] : 書き方の異なるFizzBuzzの実装 (`match`, `if`, 文字列結合, 表) と速度の計測
+//! - [`check`] : 解答として出力されたFizzBuzzの採点
+//! - [`value_at`], [`find_nth`] : 1つの値の参照と, 単語の出現位置の逆引き
+//! - [`play`] : プログラムと交互に数える対話型のゲーム
+//! - [`stats`] : 包除原理による各単語の出現数の集計
+//! - [`infer`] : FizzBuzz風の出力からのルールの推定
+//! - [`transform`] : 標準入力などから読んだ整数を1行ずつ判定する変換処理
+//! - [`Template`] : 1行ごとの出力の書式
+//! - [`write_formatted`] : JSON, CSVなどの構造化された形式での書き出し
+//! - [`Output`], [`scan`] : ファイルへの (gzip, zstdで圧縮した) 出力と, 中断した出力の再開
+//! - [`write_parallel`] : 複数スレッドで生成し, 順序を保って書き出す処理
+//! - [`serve`] : FizzBuzzを返すHTTPサーバ
+//!
+//! ```
+//! use fizz_buzz::{FizzBuzz, FizzBuzzValue};
+//!
+//! let values: Vec<_> = FizzBuzz::new(5).collect();
+//! assert_eq!(values[2], FizzBuzzValue::Fizz);
+//! assert_eq!(FizzBuzz::new(15).last().unwrap().to_string(), "FizzBuzz");
+//! ```
+
+mod big;
+mod check;
+mod color;
+mod config;
+mod format;
+mod infer;
+mod locale;
+mod names;
+mod numeral;
+mod output;
+mod parallel;
+mod play;
+mod query;
+mod rule;
+mod serve;
+mod span;
+mod stats;
+mod strategy;
+mod stream;
+mod template;
+mod write;
+
+pub use fizz_buzz_core::{
+    fizz_buzz_value, write_lines, FizzBuzz, FizzBuzzTable, FizzBuzzValue, Lines, ParseValueError,
+    SliceWriter, MAX_LINE_LEN,
+};
+
+pub use big::{classify_big, write_big, write_big_with, Decimal};
+pub use check::{check, Mismatch};
+pub use color::ColorChoice;
+pub use config::{Config, ConfigError, DEFAULT_CONFIG};
+pub use format::{write_formatted, write_values, Format, RecordWriter};
+pub use infer::{infer, Inference, Issue};
+pub use locale::Locale;
+pub use numeral::{Notation, Numerals};
+pub use output::{scan, Compression, Output, Resume, MEMBER_LEN};
+pub use parallel::{write_parallel, CHUNK_LEN};
+pub use play::{play, GameEnd, PlayOptions, Summary};
+pub use query::{can_appear, find_nth, value_at, FindError};
+pub use rule::{
+    classify, ContainsDigit, ContainsDigitIn, Custom, DivisibleBy, Integer, IsPrime, Rule, WordRule,
+};
+pub use serve::{serve, ServeOptions};
+pub use span::{Span, SpanIter};
+pub use stats::{divisors, stats, stats_up_to, Progression, Stats, StatsError, MAX_RULES};
+pub use strategy::Strategy;
+pub use stream::{transform, InvalidLine, OnInvalid};
+pub use template::{Template, TemplateError};
+pub use write::{write_fizz_buzz, write_with_rules, BUFFER_SIZE};
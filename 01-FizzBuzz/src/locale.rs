@@ -0,0 +1,41 @@
+//! 組み込みの単語の組 (`--locale ja|en|de`)
+
+use crate::{names::named, DivisibleBy, WordRule};
+
+/// 3, 5で割り切れる場合の単語の言語
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    /// Fizz, Buzz (既定)
+    #[default]
+    En,
+    /// フィズ, バズ
+    Ja,
+    /// Fiss, Buss
+    De,
+}
+
+named!(Locale, "locale", {
+    En => "en",
+    Ja => "ja",
+    De => "de",
+});
+
+impl Locale {
+    /// 3, 5で割り切れる場合の単語
+    pub fn words(&self) -> [&'static str; 2] {
+        match self {
+            Self::En => ["Fizz", "Buzz"],
+            Self::Ja => ["フィズ", "バズ"],
+            Self::De => ["Fiss", "Buss"],
+        }
+    }
+
+    /// この言語の単語による, 通常のFizzBuzzのルール
+    pub fn rules(&self) -> Vec<WordRule> {
+        let [fizz, buzz] = self.words();
+        vec![
+            WordRule::new(DivisibleBy(3), fizz),
+            WordRule::new(DivisibleBy(5), buzz),
+        ]
+    }
+}
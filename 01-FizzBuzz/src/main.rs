@@ -8,27 +8,1027 @@
 //! - 5で割り切れる数値の場合は、"Buzz"という文字列を出力します.
 //! - 3と5の両方で割り切れる数値の場合は、"FizzBuzz"という文字列を出力します.
 //! - それ以外の数値の場合は、数値を文字列としてそのまま出力します.
+//!
+//! ## ルールの変更
+//!
+//! `--rule 3=Fizz --rule 5=Buzz --rule 7=Bazz` のように除数と単語の組を指定できます.
+//! 割り切れたルールの単語はすべて, 指定された順に連結されます.
+//! `--rule contains:3=Fizz` (数字3を含む) や `--rule prime=Prime` (素数) のように, 割り切れるか以外の条件も指定できます.
+//! 指定しなかった場合は上記の3→Fizz, 5→Buzzが使われます.
+//!
+//! `--radix 16` や `--numerals roman|kanji` で, どのルールにも該当しない数値の表記を変えられます.
+//! `--rule-base` を併せて指定すると, `--rule` の除数と数字も同じ基数で読みます. (`--radix 16 --rule-base --rule a=Ten`)
+//!
+//! `--from -15 --to 15 --step 3` のように, 負の数や降順 (`--from 10 --to 1`) を含む範囲も指定できます.
+//! 0や負の数も数学的に判定します. (0はFizzBuzz, -3はFizz)
+//!
+//! `--from 10^40 --count 100` のように, u64を越える任意桁数の数値から指定個数を出力することもできます.
+//!
+//! `--stats` を指定すると, 各単語と数値の個数を包除原理で計算して出力します.
+//!
+//! `at 15` で1つの数値に対する値を, `find --word Buzz --nth 1000` で1000番目のBuzzとなる数値を表示します.
+//!
+//! `play` でプログラムと交互に数えるゲームを遊べます. (`--timeout 5` で1回の回答の制限時間を指定できます)
+//!
+//! `check --n 100 < answer.txt` で, 解答の出力を正しい出力と1行ずつ比較できます.
+//! `infer < output.txt` で, FizzBuzz風の出力からそれを生成したルールを推定し, 設定ファイルの形式で出力します.
+//!
+//! `serve --bind 127.0.0.1:8080` で, `GET /fizzbuzz?from=1&to=100&format=json` や `GET /fizzbuzz/15` に応答するHTTPサーバを起動します.
+//!
+//! `--strategy match|if|concat|table` で実装方法を切り替えられます. `bench` で各実装方法の速度を比較できます.
+//!
+//! `seq 100 200 | fizz-buzz --stdin` で, 標準入力から読んだ整数を1行ずつ判定します. `--field 2` でCSVの2列目を判定します.
+//!
+//! `--template "{n}: {word}"` で1行ごとの書式を, `--locale ja` で組み込みの単語の組 (フィズ, バズ) を指定できます.
+//!
+//! `--jobs 4` のようにスレッド数を指定すると, 複数スレッドで生成します. 出力内容は変わりません.
+//!
+//! `--format json|ndjson|csv|table` で構造化された形式でも出力できます.
+//!
+//! `--output data.txt.zst` で, 拡張子に応じて (`.gz`: gzip, `.zst`: zstd) 圧縮しながらファイルへ出力します.
+//! `--resume` を併せて指定すると, 中断したファイルの最後の完全な行を確かめ, その次の数値から続けます.
+//!
+//! 端末への出力では, 単語をルールごとの色で, 複数のルールに該当した単語を太字で表示します.
+//! `--color always|never` で切り替えられます. (`auto` では環境変数 `NO_COLOR` が設定されていれば色付けしません)
+//!
+//! `--config rules.toml` でルール, 単語の区切り, どのルールにも該当しない場合の値を設定ファイルから読み込みます.
+//! 指定しない場合も, 作業ディレクトリに `fizzbuzz.toml` があれば読み込みます.
+//!
+//! 出力先が閉じられた場合 (`| head` など) は正常終了します. 出力の書き込みに失敗した場合は終了コード74,
+//! 入力の読み込みに失敗した場合は終了コード66で終了します. (引数の誤りは2, 判定結果の不一致などは1です)
+//!
+//! 判定処理の本体はライブラリ (`src/lib.rs`) にあり, ここではその結果を出力するのみです.
+//! 通常のFizzBuzzの判定と整形は標準ライブラリを使わない `fizz-buzz-core` (`core/`) が行い, ライブラリはそれを標準入出力で包んでいます.
 
-pub fn fizz_buzz(n: u64) {
-    /* WRITE YOUR CODE HERE */
+use std::{
+    fmt,
+    io::{self, BufRead, Read, Write},
+    net::{SocketAddr, TcpListener},
+    num::{NonZeroU32, NonZeroU64, NonZeroUsize},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    process::{self, ExitCode},
+    time::Duration,
+};
+
+use fizz_buzz::{
+    check, find_nth, infer, play, scan, serve, stats, transform, write_big, write_big_with,
+    write_fizz_buzz, write_formatted, write_parallel, write_values, write_with_rules, ColorChoice,
+    Config, Decimal, Format, Locale, Notation, Numerals, OnInvalid, Output, PlayOptions,
+    Progression, ServeOptions, Span, Strategy, Template, WordRule, DEFAULT_CONFIG,
+};
+
+fn fizz_buzz<W: Write>(
+    out: W,
+    range: RangeInclusive<u64>,
+    config: &Config,
+    jobs: NonZeroUsize,
+    format: Format,
+) -> io::Result<()> {
+    // `--jobs`はclapで`--format`と併用できないため, text形式のみ
+    if jobs.get() > 1 {
+        return write_parallel(out, range, jobs, |buf, chunk| {
+            write_range(buf, chunk, config, format)
+        });
+    }
+
+    write_range(out, range, config, format)
+}
+
+/// 指定の実装方法で1からNまでを出力する. 通常のFizzBuzzのルールのみ対応する
+fn fizz_buzz_strategy<W: Write>(
+    out: W,
+    range: RangeInclusive<u64>,
+    strategy: Strategy,
+    jobs: NonZeroUsize,
+) -> io::Result<()> {
+    if jobs.get() > 1 {
+        return write_parallel(out, range, jobs, |buf, chunk| strategy.write(buf, chunk));
+    }
+
+    strategy.write(out, range)
+}
+
+/// 各実装方法で1からNまでを生成し, 1つの値あたりの時間を出力する
+fn fizz_buzz_bench(n: u64, strategies: &[Strategy]) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    for &strategy in strategies {
+        let elapsed = strategy.bench(n)?;
+        let per_value = elapsed.as_nanos() as f64 / n.max(1) as f64;
+        writeln!(
+            stdout,
+            "{:<8} {:>10.3} s {:>10.2} ns/value",
+            strategy.name(),
+            elapsed.as_secs_f64(),
+            per_value
+        )?;
+    }
+
+    Ok(())
+}
+
+/// 標準入力から1行ずつ読んだ整数を判定して出力する
+fn fizz_buzz_stdin<W: Write>(
+    out: W,
+    config: &Config,
+    field: Option<NonZeroUsize>,
+    on_invalid: OnInvalid,
+) -> io::Result<()> {
+    let result = transform(Input(io::stdin().lock()), out, config, field, on_invalid);
+
+    match rule_error(result)? {
+        Ok(_) => Ok(()),
+        Err(invalid) => Err(failure(Failure::Error(invalid.to_string()))),
+    }
+}
+
+/// 区切りや置き換えのない設定では, できるだけ高速な経路で出力する
+fn write_range<W: Write>(
+    out: W,
+    range: RangeInclusive<u64>,
+    config: &Config,
+    format: Format,
+) -> io::Result<()> {
+    let rules = &config.rules;
+    if !config.is_plain() {
+        return write_values(out, range, format, config.value_width(), |n| {
+            config.value(n)
+        });
+    }
+
+    match format {
+        Format::Text if WordRule::is_classic(rules) => write_fizz_buzz(out, range),
+        Format::Text => write_with_rules(out, range, rules),
+        _ => write_formatted(out, range, rules, format),
+    }
 }
 
+/// 任意の範囲を出力する. 負の数も数学的に判定する (0はFizzBuzz, -3はFizz)
+fn fizz_buzz_span<W: Write>(out: W, span: Span, config: &Config, format: Format) -> io::Result<()> {
+    if config.is_plain() {
+        write_formatted(out, span, &config.rules, format)
+    } else {
+        write_values(out, span, format, config.value_width(), |n| config.value(n))
+    }
+}
 
+/// `from`から`count`個の数値を出力する. u64を越える数値も扱える
+fn fizz_buzz_big<W: Write>(
+    out: W,
+    from: Decimal,
+    step: NonZeroU64,
+    count: u64,
+    config: &Config,
+    format: Format,
+) -> io::Result<()> {
+    let result = if config.is_plain() {
+        write_big(out, from, step, count, &config.rules, format)
+    } else {
+        write_big_with(
+            out,
+            from,
+            step,
+            count,
+            format,
+            config.value_width(),
+            |n, buf| {
+                let value = config
+                    .value_big(n)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                *buf += &value;
+                Ok(())
+            },
+        )
+    };
 
+    rule_error(result)
+}
 
+/// 範囲内の各単語の個数を出力する. 1つずつ判定せず, 包除原理で計算する
+fn fizz_buzz_stats<W: Write>(mut out: W, values: Progression, config: &Config) -> io::Result<()> {
+    if !config.separator.is_empty() {
+        return Err(failure(Failure::Usage(
+            "--stats cannot be used with a word separator".to_string(),
+        )));
+    }
+    let stats = stats(values, &config.rules).map_err(|e| failure(Failure::Usage(e.to_string())))?;
+    write!(out, "{}", stats)
+}
 
 ///// 以下は実行処理本体. /////
 
-use clap::Parser;
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand};
+
+/// `--from`の値. i64に収まらない場合は任意桁数の数値として扱う
+#[derive(Clone)]
+enum Start {
+    Small(i64),
+    Big(Decimal),
+}
+
+/// `NUMBER`の値. `10^18`のような累乗の形式も受け付ける
+fn parse_number(s: &str) -> Result<u64, String> {
+    if let Ok(n) = s.parse() {
+        return Ok(n);
+    }
+    let too_large = || format!("`{}` is too large (must be at most {})", s, u64::MAX);
+
+    // 累乗は展開する前に, u64に収まるかを調べる
+    if let Some((base, exp)) = s.split_once('^') {
+        if let (Ok(base), Ok(exp)) = (base.parse::<u64>(), exp.parse::<u32>()) {
+            return base.checked_pow(exp).ok_or_else(too_large);
+        }
+    }
+    let n: Decimal = s.parse()?;
+    n.to_u64().ok_or_else(too_large)
+}
+
+/// 秒数. `0.5`のような小数も受け付ける
+fn parse_seconds(s: &str) -> Result<Duration, String> {
+    let seconds: f64 = s
+        .parse()
+        .map_err(|e| format!("invalid seconds `{}`: {}", s, e))?;
+    Duration::try_from_secs_f64(seconds)
+        .ok()
+        .filter(|d| !d.is_zero())
+        .ok_or_else(|| format!("seconds must be greater than 0, got `{}`", s))
+}
+
+fn parse_start(s: &str) -> Result<Start, String> {
+    if let Ok(n) = s.parse() {
+        return Ok(Start::Small(n));
+    }
+    let n: Decimal = s.parse()?;
+    match n.to_u64().and_then(|n| i64::try_from(n).ok()) {
+        Some(n) => Ok(Start::Small(n)),
+        None => Ok(Start::Big(n)),
+    }
+}
 
 #[derive(Parser)]
-#[command(about)]
+#[command(
+    about,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Args {
-    number: u64,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// 1からこの数値までを出力します
+    #[arg(
+        required_unless_present_any = ["to", "count", "stdin"],
+        conflicts_with_all = ["from", "to", "count", "stdin"],
+        value_parser = parse_number
+    )]
+    number: Option<u64>,
+
+    /// 範囲の開始値. `--count`と併用する場合は`10^40`のような任意桁数の数値も指定できます [既定: 1]
+    #[arg(long, allow_negative_numbers = true, value_parser = parse_start)]
+    from: Option<Start>,
+
+    /// 範囲の終了値. 開始値より小さい場合は降順に出力します
+    #[arg(long, allow_negative_numbers = true, conflicts_with = "count")]
+    to: Option<i64>,
+
+    /// 開始値から出力する個数
+    #[arg(long)]
+    count: Option<u64>,
+
+    /// 増分. 降順の場合も正の値で指定します [既定: 1]
+    #[arg(long)]
+    step: Option<NonZeroU64>,
+
+    /// ルールと単語の組 (例: `--rule 3=Fizz --rule 5=Buzz --rule contains:7=Bazz --rule prime=Prime`)
+    ///
+    /// 設定ファイルのルールより優先されます.
+    #[arg(long = "rule", value_name = "RULE=WORD", global = true)]
+    rules: Vec<String>,
+
+    /// 組み込みの単語の組 (en: Fizz/Buzz, ja: フィズ/バズ, de: Fiss/Buss). 設定ファイルのルールより優先されます
+    #[arg(long, global = true, conflicts_with = "rules")]
+    locale: Option<Locale>,
+
+    /// ルールの設定ファイル (TOML, JSON). 指定しない場合は作業ディレクトリの`fizzbuzz.toml`があれば読み込みます
+    #[arg(long, value_name = "PATH", global = true)]
+    config: Option<PathBuf>,
+
+    /// 生成に使うスレッド数. 出力は1スレッドの場合と同一です (1からNまでのtext形式のみ)
+    #[arg(
+        short,
+        long,
+        default_value = "1",
+        conflicts_with_all = ["from", "to", "count", "step", "format"]
+    )]
+    jobs: NonZeroUsize,
+
+    /// どのルールにも該当しない数値を表す基数 (2から36. 10以上の桁は小文字のアルファベット)
+    #[arg(long, value_name = "N", global = true, value_parser = clap::value_parser!(u32).range(2..=36))]
+    radix: Option<u32>,
+
+    /// どのルールにも該当しない数値の記数法 (roman: 1から3999, kanji: 0以上). 表せない数値は10進数で出力します
+    #[arg(long, global = true, conflicts_with = "radix")]
+    numerals: Option<Numerals>,
+
+    /// `--rule`の除数と`contains:`の数字を`--radix`の基数で読みます
+    #[arg(long, global = true, requires = "radix")]
+    rule_base: bool,
+
+    /// 単語の色付け (auto: 端末への出力で`NO_COLOR`がない場合のみ, always, never). text形式のみ [既定: auto]
+    #[arg(long, value_name = "WHEN", global = true)]
+    color: Option<ColorChoice>,
+
+    /// 出力形式 (text, json, ndjson, csv, table)
+    #[arg(short, long, default_value = "text")]
+    format: Format,
+
+    /// 標準出力の代わりにファイルへ出力します. 拡張子が`.gz`ならgzip, `.zst`ならzstdで圧縮します
+    #[arg(short, long, value_name = "PATH")]
+    output: Option<PathBuf>,
+
+    /// `--output`のファイルが既にあれば, その完全な行の次の数値から続けて出力します (text形式のみ)
+    #[arg(
+        long,
+        requires = "output",
+        conflicts_with_all = ["format", "stats", "strategy", "template", "stdin"]
+    )]
+    resume: bool,
+
+    /// 出力せずに, 範囲内の各単語と数値の個数のみを出力します
+    #[arg(long, conflicts_with_all = ["jobs", "format"])]
+    stats: bool,
+
+    /// 実装方法 (match, if, concat, table). 1からNまでの通常のFizzBuzzのみ対応します
+    #[arg(
+        long,
+        conflicts_with_all = ["from", "to", "count", "step", "format", "stats", "radix", "numerals", "color"]
+    )]
+    strategy: Option<Strategy>,
+
+    /// 1行ごとの出力の書式. `{n}` (数値), `{word}` (値), `{index}` (行番号. `{index:4}`で0埋め) を使えます
+    #[arg(
+        long,
+        conflicts_with_all = ["format", "stats", "strategy", "stdin"]
+    )]
+    template: Option<Template>,
+
+    /// 1からNまでを生成する代わりに, 標準入力から1行ずつ読んだ整数を判定します
+    #[arg(
+        long,
+        conflicts_with_all = ["from", "to", "count", "step", "format", "stats", "strategy", "jobs"]
+    )]
+    stdin: bool,
+
+    /// `--stdin`で, 各行をカンマで区切ったN列目 (1始まり) の整数を判定し, その列を値に置き換えます
+    #[arg(long, value_name = "N", requires = "stdin")]
+    field: Option<NonZeroUsize>,
+
+    /// `--stdin`で整数として解析できない行の扱い (pass: そのまま出力, skip: 出力しない, error: 行番号を表示して終了) [既定: error]
+    #[arg(long, value_name = "ACTION", requires = "stdin")]
+    on_invalid: Option<OnInvalid>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 数値NUMBERに対して出力される値を表示します
+    At {
+        /// `10^40`のような任意桁数の数値や負の数も指定できます
+        #[arg(allow_negative_numbers = true, value_parser = parse_start)]
+        number: Start,
+    },
+    /// 標準入力から読んだ解答を, 1からNまでの正しい出力と比較します
+    ///
+    /// 一致した場合は終了コード0, 相違があった場合は最初の相違点を表示して終了コード1で終了します.
+    Check {
+        #[arg(long, value_parser = parse_number)]
+        n: u64,
+    },
+    /// 各実装方法で1からNまでを生成し (出力は捨てる), 1つの値あたりの時間を表示します
+    Bench {
+        #[arg(long, default_value = "10000000", value_parser = parse_number)]
+        n: u64,
+
+        /// 計測する実装方法. 指定しない場合はすべて計測します
+        #[arg(long)]
+        strategy: Vec<Strategy>,
+    },
+    /// プログラムと交互に数えるゲームを遊びます. 奇数はプログラムが, 偶数はあなたが答えます
+    ///
+    /// ルールは`--rule`, `--locale`, 設定ファイルで変更できます. `q`を入力すると終了し, 最後に結果を表示します.
+    Play {
+        /// 最後の数値
+        #[arg(long, default_value = "100", value_parser = parse_number)]
+        n: u64,
+
+        /// 間違えられる回数
+        #[arg(long, default_value = "3")]
+        lives: NonZeroU32,
+
+        /// 1回の回答の制限時間 (秒)
+        #[arg(long, value_name = "SECONDS", value_parser = parse_seconds)]
+        timeout: Option<Duration>,
+    },
+    /// 単語WORDがNTH番目に出力される数値 (1以上) を表示します
+    Find {
+        #[arg(long)]
+        word: String,
+
+        #[arg(long, default_value = "1")]
+        nth: NonZeroU64,
+    },
+    /// 標準入力から読んだFizzBuzz風の出力から, それを生成した除数と単語のルールを推定します
+    ///
+    /// `FizzBuzzBazz`のような連結された単語も分解し, 推定したルールを`--config`で読み込める形式で出力します.
+    /// 曖昧な点は警告として表示し, 矛盾がある場合は終了コード1で終了します.
+    Infer {
+        /// 1行目の数値. 指定しない場合は数値の行から求めます (数値の行がなければ1)
+        #[arg(long, value_parser = parse_number)]
+        from: Option<u64>,
+    },
+    /// HTTPで値を返すサーバを起動します
+    ///
+    /// `GET /fizzbuzz?from=1&to=100&format=json` で範囲内の値を, `GET /fizzbuzz/15` で1つの値を返します.
+    /// ルールは`--rule`, `--locale`, 設定ファイルで変更できます.
+    Serve {
+        /// 待ち受けるアドレス. ポート0を指定すると空いているポートを使います
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        bind: SocketAddr,
+
+        /// 1回の要求で返せる値の個数の上限
+        #[arg(long, default_value = "1000000", value_parser = parse_number)]
+        max_range: u64,
+
+        /// 同時に応答する接続の数. 越えた接続は空きが出るまで待たせます
+        #[arg(long, default_value = "64")]
+        max_connections: NonZeroUsize,
+    },
+}
+
+/// 出力対象の範囲
+enum Target {
+    /// 1からNまで (`--resume`の場合は続きの数値から)
+    UpTo(RangeInclusive<u64>),
+    /// 負の数や降順, 増分を含む範囲
+    Span(Span),
+    /// 開始値からの個数. u64を越える数値も扱える
+    Count(Decimal, u64),
 }
 
-fn main() {
+impl Target {
+    fn new(args: &Args, step: NonZeroU64) -> Self {
+        let start = args.from.clone().unwrap_or(Start::Small(1));
+
+        match (args.number, args.to, args.count, start) {
+            // `Span`は空の範囲を表せないため, 0は増分があっても空の範囲とする
+            (Some(n), ..) if args.step.is_none() || n == 0 => Self::UpTo(1..=n),
+            (Some(n), ..) => {
+                let to = i64::try_from(n).unwrap_or_else(|_| {
+                    fail(format!(
+                        "NUMBER must be at most {} when used with --step",
+                        i64::MAX
+                    ))
+                });
+                Self::Span(Span::new(1, to, step))
+            }
+            (None, Some(to), _, Start::Small(from)) => Self::Span(Span::new(from, to, step)),
+            (None, Some(_), _, Start::Big(_)) => fail(format!(
+                "--from must be within {}..={} when used with --to (use --count instead)",
+                i64::MIN,
+                i64::MAX
+            )),
+            (None, None, Some(count), Start::Big(n)) => Self::Count(n, count),
+            (None, None, Some(count), Start::Small(n)) if n >= 0 => {
+                Self::Count(Decimal::from(n.unsigned_abs()), count)
+            }
+            (None, None, Some(0), Start::Small(_)) => Self::Count(Decimal::from(0), 0),
+            // 負の数から始まる場合は, 終了値がi64に収まる範囲のみ扱う
+            (None, None, Some(count), Start::Small(n)) => {
+                let offset = i128::from(step.get()) * i128::from(count - 1);
+                let to = i64::try_from(i128::from(n) + offset)
+                    .unwrap_or_else(|_| fail(format!("--count is too large for --from {}", n)));
+                Self::Span(Span::new(n, to, step))
+            }
+            (None, None, None, _) => unreachable!("clap requires NUMBER, --to, --count or --stdin"),
+        }
+    }
+
+    /// 出力する値の個数
+    fn len(&self) -> u128 {
+        match self {
+            Self::UpTo(range) if range.is_empty() => 0,
+            Self::UpTo(range) => u128::from(range.end() - range.start()) + 1,
+            Self::Span(span) => u128::from(span.count()),
+            Self::Count(_, count) => u128::from(*count),
+        }
+    }
+
+    /// `i`番目 (0始まり) の値
+    fn value(&self, i: u64, step: NonZeroU64, config: &Config) -> Result<String, String> {
+        match self {
+            Self::UpTo(range) => Ok(config.value(range.start() + i)),
+            Self::Span(span) => Ok(config.value(span.get(i).expect("i is within the span"))),
+            Self::Count(from, _) => {
+                let mut n = from.clone();
+                n.add(offset(i, step)?);
+                config.value_big(&n)
+            }
+        }
+    }
+
+    /// 先頭の`k`個を除いた残りの範囲. `k`は[`Target::len`]以下とする
+    fn skip(self, k: u64, step: NonZeroU64) -> Self {
+        if u128::from(k) == self.len() {
+            return Self::Count(Decimal::from(0), 0);
+        }
+        match self {
+            Self::UpTo(range) => Self::UpTo(range.start() + k..=*range.end()),
+            Self::Span(span) => {
+                let from = span.get(k).expect("k is within the span");
+                Self::Span(Span::new(from, span.to, span.step))
+            }
+            Self::Count(mut from, count) => {
+                from.add(offset(k, step).unwrap_or_else(|e| fail(e)));
+                Self::Count(from, count - k)
+            }
+        }
+    }
+
+    fn progression(&self, step: NonZeroU64) -> Result<Progression, String> {
+        match self {
+            // `--stats`は`--resume`と併用できないため, 常に1から始まる
+            Self::UpTo(range) => Ok(Progression::up_to(*range.end())),
+            Self::Span(span) => Ok(Progression::from(*span)),
+            Self::Count(from, count) => {
+                let from = from
+                    .to_u64()
+                    .ok_or_else(|| format!("--from must be at most {} with --stats", u64::MAX))?;
+                Ok(Progression::new(i128::from(from), step, u128::from(*count)))
+            }
+        }
+    }
+}
+
+// `i`個分の増分. u64を越える場合はエラー
+fn offset(i: u64, step: NonZeroU64) -> Result<u64, String> {
+    i.checked_mul(step.get())
+        .ok_or_else(|| format!("{} steps of {} exceed {}", i, step, u64::MAX))
+}
+
+/// 入力の読み込みに失敗した場合の終了コード (sysexits.hのEX_NOINPUT)
+const EXIT_INPUT: u8 = 66;
+/// 出力の書き込みに失敗した場合の終了コード (sysexits.hのEX_IOERR)
+const EXIT_OUTPUT: u8 = 74;
+
+fn main() -> ExitCode {
+    let Err(e) = run() else {
+        return ExitCode::SUCCESS;
+    };
+    // 判定や引数のエラーは, 出力を閉じた後に表示する
+    if let Some(failure) = e.get_ref().and_then(|e| e.downcast_ref::<Failure>()) {
+        return failure.exit();
+    }
+
+    match e.get_ref().and_then(|e| e.downcast_ref::<InputError>()) {
+        Some(InputError(e)) => {
+            eprintln!("error: failed to read input: {}", e);
+            ExitCode::from(EXIT_INPUT)
+        }
+        // 出力先が閉じられた (`| head`など) のは正常終了とする
+        None if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        None => {
+            eprintln!("error: failed to write output: {}", e);
+            ExitCode::from(EXIT_OUTPUT)
+        }
+    }
+}
+
+fn run() -> io::Result<()> {
     // コマンドライン引数の解析
     let args = Args::parse();
-    fizz_buzz(args.number);
+    let mut config = load_config(args.config.as_deref());
+    if let Some(locale) = args.locale {
+        config.rules = locale.rules();
+    }
+    if !args.rules.is_empty() {
+        config.rules = parse_rules(&args);
+    }
+    if let Some(radix) = args.radix {
+        config.notation = Notation::Radix(radix);
+    }
+    if let Some(numerals) = args.numerals {
+        config.notation = numerals.into();
+    }
+    let color = match args.output {
+        // ファイルへの出力は`--color always`の場合のみ色付けする
+        Some(_) => args.color.unwrap_or_default().enabled(false, false),
+        None => args.color.unwrap_or_default().enabled_for_stdout(),
+    };
+
+    match args.command {
+        Some(Command::At { number }) => {
+            config.color = color;
+            return fizz_buzz_at(number, &config);
+        }
+        Some(Command::Find { word, nth }) => return fizz_buzz_find(&word, nth, &config),
+        Some(Command::Serve {
+            bind,
+            max_range,
+            max_connections,
+        }) => {
+            let options = ServeOptions {
+                max_range,
+                max_connections,
+            };
+            return fizz_buzz_serve(bind, config, options);
+        }
+        Some(Command::Check { n }) => return fizz_buzz_check(n, &config),
+        Some(Command::Infer { from }) => return fizz_buzz_infer(from),
+        Some(Command::Bench { n, strategy }) if strategy.is_empty() => {
+            return fizz_buzz_bench(n, &Strategy::ALL)
+        }
+        Some(Command::Bench { n, strategy }) => return fizz_buzz_bench(n, &strategy),
+        Some(Command::Play { n, lives, timeout }) => {
+            let options = PlayOptions {
+                n,
+                lives: lives.get(),
+                timeout,
+            };
+            return fizz_buzz_play(&config, options);
+        }
+        None => {}
+    }
+
+    if args.stdin {
+        config.color = color;
+        let mut out = create_output(args.output.as_deref());
+        let result = fizz_buzz_stdin(
+            &mut out,
+            &config,
+            args.field,
+            args.on_invalid.unwrap_or_default(),
+        );
+        return finish(out, result);
+    }
+    // NUMBERは`--stdin`と競合するため, clapは`requires`を検査しない
+    if args.field.is_some() || args.on_invalid.is_some() {
+        fail("--field and --on-invalid can only be used with --stdin".to_string());
+    }
+
+    let step = args.step.unwrap_or(NonZeroU64::MIN);
+    let target = Target::new(&args, step);
+    // 構造化された形式の値と`--strategy`の出力には色を付けない
+    config.color = color && args.format == Format::Text && args.strategy.is_none();
+
+    let (target, mut out) = match &args.output {
+        Some(path) if args.resume => match resume_output(path, target, step, &config) {
+            Some(resumed) => resumed,
+            // 既にすべて出力済み
+            None => return Ok(()),
+        },
+        output => (target, create_output(output.as_deref())),
+    };
+
+    let result = write_target(&mut out, &args, target, step, &config);
+    finish(out, result)
+}
+
+// 引数に応じた方法で範囲を出力する
+fn write_target(
+    out: &mut Output,
+    args: &Args,
+    target: Target,
+    step: NonZeroU64,
+    config: &Config,
+) -> io::Result<()> {
+    if args.stats {
+        let values = target
+            .progression(step)
+            .map_err(|e| failure(Failure::Usage(e)))?;
+        return fizz_buzz_stats(out, values, config);
+    }
+
+    if let Some(strategy) = args.strategy {
+        if !(config.is_plain() && WordRule::is_classic(&config.rules)) {
+            return Err(failure(Failure::Usage(
+                "--strategy supports only the classic rules (3=Fizz, 5=Buzz)".to_string(),
+            )));
+        }
+        let Target::UpTo(range) = target else {
+            unreachable!("clap rejects --strategy with a range")
+        };
+        return fizz_buzz_strategy(out, range, strategy, args.jobs);
+    }
+
+    if let Some(template) = &args.template {
+        return fizz_buzz_template(out, target, step, config, template, args.jobs);
+    }
+
+    match target {
+        // 1からNまでの単純な範囲は, u64の上限まで扱える高速な経路で出力する
+        Target::UpTo(range) => fizz_buzz(out, range, config, args.jobs, args.format),
+        Target::Span(span) => fizz_buzz_span(out, span, config, args.format),
+        Target::Count(from, count) => fizz_buzz_big(out, from, step, count, config, args.format),
+    }
+}
+
+// エラーで中断した場合も, 書き出した分の圧縮形式の終端までを書き出す. 先に起きたエラーを返す
+fn finish(out: Output, result: io::Result<()>) -> io::Result<()> {
+    let finished = out.finish();
+    result.and(finished)
+}
+
+// `--output`で指定されたファイル (拡張子に応じて圧縮する), または標準出力
+fn create_output(path: Option<&Path>) -> Output {
+    let Some(path) = path else {
+        return Output::stdout();
+    };
+    Output::create(path).unwrap_or_else(|e| {
+        eprintln!("error: failed to create {}: {}", path.display(), e);
+        process::exit(EXIT_OUTPUT.into())
+    })
+}
+
+// `--resume`: 既存のファイルの完全な行が正しい出力の先頭と一致するか確かめ,
+// 残りの範囲とその続きを書き出す出力を返す. 途中までの行は書き直す. 既にすべて出力済みなら`None`
+fn resume_output(
+    path: &Path,
+    target: Target,
+    step: NonZeroU64,
+    config: &Config,
+) -> Option<(Target, Output)> {
+    let resume = match scan(path) {
+        Ok(Some(resume)) => resume,
+        Ok(None) => return Some((target, create_output(Some(path)))),
+        Err(e) => {
+            eprintln!("error: failed to read {}: {}", path.display(), e);
+            process::exit(EXIT_INPUT.into())
+        }
+    };
+    let cannot_resume = |reason: String| -> ! {
+        eprintln!("error: cannot resume {}: {}", path.display(), reason);
+        process::exit(1)
+    };
+
+    let lines = resume.lines;
+    let len = target.len();
+    if u128::from(lines) > len {
+        cannot_resume(format!(
+            "it has {} lines, more than the {} values to output",
+            lines, len
+        ));
+    }
+    if let (Some(last_line), Some(i)) = (&resume.last_line, lines.checked_sub(1)) {
+        let expected = target
+            .value(i, step, config)
+            .unwrap_or_else(|e| cannot_resume(e));
+        if *last_line != expected {
+            cannot_resume(format!(
+                "line {} is {:?}, expected {:?}",
+                lines, last_line, expected
+            ));
+        }
+    }
+    if u128::from(lines) == len && resume.is_clean() {
+        return None;
+    }
+
+    let target = target.skip(lines, step);
+    let output = Output::append(path, &resume).unwrap_or_else(|e| {
+        eprintln!("error: failed to resume {}: {}", path.display(), e);
+        process::exit(EXIT_OUTPUT.into())
+    });
+    Some((target, output))
+}
+
+// `--rule`を解析する. `--rule-base`の場合は除数と数字を`--radix`の基数で読む
+fn parse_rules(args: &Args) -> Vec<WordRule> {
+    let radix = match args.radix {
+        Some(radix) if args.rule_base => radix,
+        _ => 10,
+    };
+    args.rules
+        .iter()
+        .map(|s| {
+            WordRule::from_str_radix(s, radix).unwrap_or_else(|e| {
+                fail(format!(
+                    "invalid value '{}' for '--rule <RULE=WORD>': {}",
+                    s, e
+                ))
+            })
+        })
+        .collect()
+}
+
+// `--config`で指定されたファイル, または作業ディレクトリの`fizzbuzz.toml`を読み込む
+fn load_config(path: Option<&Path>) -> Config {
+    let path = match path {
+        Some(path) => path,
+        None if Path::new(DEFAULT_CONFIG).is_file() => Path::new(DEFAULT_CONFIG),
+        None => return Config::default(),
+    };
+
+    Config::load(path).unwrap_or_else(|e| {
+        eprintln!("error: {}: {}", path.display(), e);
+        process::exit(1)
+    })
+}
+
+// 1行ごとに書式を適用して出力する. 行番号は1から数える
+fn fizz_buzz_template<W: Write>(
+    out: W,
+    target: Target,
+    step: NonZeroU64,
+    config: &Config,
+    template: &Template,
+    jobs: NonZeroUsize,
+) -> io::Result<()> {
+    let mut index = 0;
+
+    match target {
+        // 1からNまでは行番号が数値と等しいため, 並列にも生成できる
+        Target::UpTo(range) if jobs.get() > 1 => write_parallel(out, range, jobs, |buf, chunk| {
+            write_values(buf, chunk, Format::Text, 0, |i| {
+                template.render(i, i, &config.value(i))
+            })
+        }),
+        Target::UpTo(range) => write_values(out, range, Format::Text, 0, |i| {
+            template.render(i, i, &config.value(i))
+        }),
+        Target::Span(span) => write_values(out, span, Format::Text, 0, |n| {
+            index += 1;
+            template.render(index, n, &config.value(n))
+        }),
+        Target::Count(from, count) => rule_error(write_big_with(
+            out,
+            from,
+            step,
+            count,
+            Format::Text,
+            0,
+            |n, buf| {
+                let value = config
+                    .value_big(n)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                index += 1;
+                *buf += &template.render(index, n, &value);
+                Ok(())
+            },
+        )),
+    }
+}
+
+fn fizz_buzz_play(config: &Config, options: PlayOptions) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    let summary = play(Input(io::stdin()), &mut stdout, config, options)?;
+    write!(stdout, "{}", summary)
+}
+
+// 待ち受けを始めたら, 実際のアドレスを表示する (ポート0を指定した場合のため)
+fn fizz_buzz_serve(bind: SocketAddr, config: Config, options: ServeOptions) -> io::Result<()> {
+    let listener = TcpListener::bind(bind).unwrap_or_else(|e| {
+        eprintln!("error: failed to bind {}: {}", bind, e);
+        process::exit(1)
+    });
+    let addr = listener.local_addr()?;
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "listening on http://{}", addr)?;
+    stdout.flush()?;
+    drop(stdout);
+
+    serve(listener, config, options)
+}
+
+fn fizz_buzz_at(number: Start, config: &Config) -> io::Result<()> {
+    let value = match number {
+        Start::Small(n) => config.value(n),
+        Start::Big(n) => config.value_big(&n).unwrap_or_else(|e| fail(e)),
+    };
+    writeln!(io::stdout().lock(), "{}", value)
+}
+
+fn fizz_buzz_find(word: &str, nth: NonZeroU64, config: &Config) -> io::Result<()> {
+    if !config.separator.is_empty() {
+        fail("find cannot be used with a word separator".to_string());
+    }
+    match find_nth(word, nth, &config.rules) {
+        Ok(n) => writeln!(io::stdout().lock(), "{}", n),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1)
+        }
+    }
+}
+
+fn fizz_buzz_check(n: u64, config: &Config) -> io::Result<()> {
+    let expected = (1..=n).map(|i| config.value(i));
+    match check(Input(io::stdin().lock()), expected)? {
+        Ok(lines) => writeln!(io::stdout().lock(), "ok: {} lines match", lines),
+        Err(mismatch) => {
+            writeln!(io::stdout().lock(), "{}", mismatch)?;
+            process::exit(1)
+        }
+    }
+}
+
+fn fizz_buzz_infer(start: Option<u64>) -> io::Result<()> {
+    // 推定できない入力も, 読み込みのエラーとして扱う
+    let inference = infer(Input(io::stdin().lock()), start).map_err(input_error)?;
+    write!(io::stdout().lock(), "{}", inference.to_toml())?;
+
+    for issue in &inference.issues {
+        let level = if issue.is_contradiction() {
+            "error"
+        } else {
+            "warning"
+        };
+        eprintln!("{}: {}", level, issue);
+    }
+    if !inference.is_consistent() {
+        process::exit(1)
+    }
+    Ok(())
+}
+
+/// 読み込みのエラーを書き込みのエラーと区別するための入力
+struct Input<R>(R);
+
+#[derive(Debug)]
+struct InputError(io::Error);
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for InputError {}
+
+fn input_error(e: io::Error) -> io::Error {
+    io::Error::new(e.kind(), InputError(e))
+}
+
+impl<R: Read> Read for Input<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf).map_err(input_error)
+    }
+}
+
+impl<R: BufRead> BufRead for Input<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.0.fill_buf().map_err(input_error)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.0.consume(amt)
+    }
+}
+
+/// 出力を閉じてから表示し, 終了コード1または2で終了するエラー
+#[derive(Debug)]
+enum Failure {
+    /// 引数の検証エラー (終了コード2)
+    Usage(String),
+    /// 判定できない値や, 整数として解析できない入力行 (終了コード1)
+    Error(String),
+}
+
+impl Failure {
+    fn exit(&self) -> ExitCode {
+        match self {
+            Self::Usage(message) => fail(message.clone()),
+            Self::Error(message) => {
+                eprintln!("error: {}", message);
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Usage(message) | Self::Error(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for Failure {}
+
+fn failure(failure: Failure) -> io::Error {
+    io::Error::other(failure)
+}
+
+// u64を越える数値に適用できないルールがあった場合は, 判定のエラーとする
+fn rule_error<T>(result: io::Result<T>) -> io::Result<T> {
+    result.map_err(|e| match e.kind() {
+        io::ErrorKind::InvalidInput => failure(Failure::Error(e.to_string())),
+        _ => e,
+    })
+}
+
+// 引数の検証エラーとして終了する
+fn fail(message: String) -> ! {
+    Args::command()
+        .error(ErrorKind::ValueValidation, message)
+        .exit()
 }
@@ -0,0 +1,48 @@
+//! コマンドライン引数などで名前を指定する列挙型の共通の実装
+
+/// 各値の名前から, `ALL`, `name`, [`FromStr`](std::str::FromStr) (大文字と小文字を区別しない),
+/// [`Display`](std::fmt::Display) を実装する
+///
+/// 解析できない名前のエラーは ``unknown <kind> `<name>` (expected one of: <names>)`` になります.
+macro_rules! named {
+    ($type:ident, $kind:literal, { $($variant:ident => $name:literal),+ $(,)? }) => {
+        impl $type {
+            /// すべての値 (名前の一覧の順)
+            pub const ALL: [Self; [$($name),+].len()] = [$(Self::$variant),+];
+
+            /// コマンドライン引数などで指定する名前
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $name),+
+                }
+            }
+        }
+
+        impl ::std::str::FromStr for $type {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::ALL
+                    .into_iter()
+                    .find(|value| value.name().eq_ignore_ascii_case(s))
+                    .ok_or_else(|| {
+                        let names: Vec<_> = Self::ALL.iter().map(Self::name).collect();
+                        format!(
+                            "unknown {} `{}` (expected one of: {})",
+                            $kind,
+                            s,
+                            names.join(", ")
+                        )
+                    })
+            }
+        }
+
+        impl ::std::fmt::Display for $type {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+    };
+}
+
+pub(crate) use named;
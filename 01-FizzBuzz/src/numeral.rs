@@ -0,0 +1,131 @@
+//! どのルールにも該当しない数値の表記 (`--radix 16`, `--numerals kanji`)
+
+use crate::{names::named, Integer};
+
+/// 数値の表記
+///
+/// 表せない数値 (ローマ数字の0や4000以上, ローマ数字と漢数字の負の数) は10進数のまま出力します.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Notation {
+    /// 10進数 (既定)
+    #[default]
+    Decimal,
+    /// 2進数から36進数 (10以上の桁は小文字のアルファベット)
+    Radix(u32),
+    /// ローマ数字 (1から3999)
+    Roman,
+    /// 漢数字 (`十五`, `一万二千`)
+    Kanji,
+}
+
+impl Notation {
+    /// 数値`n`をこの表記で表した文字列
+    pub fn format<N: Integer>(self, n: N) -> String {
+        let magnitude = n.magnitude();
+        match self {
+            Self::Radix(radix) if radix != 10 => {
+                let sign = if n.is_negative() { "-" } else { "" };
+                format!("{}{}", sign, radix_digits(magnitude, radix))
+            }
+            Self::Roman if !n.is_negative() && (1..=3999).contains(&magnitude) => roman(magnitude),
+            Self::Kanji if !n.is_negative() => kanji(magnitude),
+            _ => n.to_string(),
+        }
+    }
+}
+
+impl From<Numerals> for Notation {
+    fn from(numerals: Numerals) -> Self {
+        match numerals {
+            Numerals::Roman => Self::Roman,
+            Numerals::Kanji => Self::Kanji,
+        }
+    }
+}
+
+/// `--numerals`で選べる記数法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Numerals {
+    /// ローマ数字
+    Roman,
+    /// 漢数字
+    Kanji,
+}
+
+named!(Numerals, "numerals", {
+    Roman => "roman",
+    Kanji => "kanji",
+});
+
+fn radix_digits(mut n: u64, radix: u32) -> String {
+    let radix = u64::from(radix);
+    let mut digits = Vec::new();
+    loop {
+        let digit = char::from_digit((n % radix) as u32, radix as u32).expect("radix is 2..=36");
+        digits.push(digit);
+        n /= radix;
+        if n == 0 {
+            break;
+        }
+    }
+
+    digits.iter().rev().collect()
+}
+
+fn roman(mut n: u64) -> String {
+    const SYMBOLS: [(u64, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+
+    let mut s = String::new();
+    for (value, symbol) in SYMBOLS {
+        while n >= value {
+            s += symbol;
+            n -= value;
+        }
+    }
+    s
+}
+
+// 4桁ごとに万, 億, 兆, 京で区切る. 十, 百, 千の前の一は省く (一万の一は省かない)
+fn kanji(n: u64) -> String {
+    const DIGITS: [&str; 10] = ["〇", "一", "二", "三", "四", "五", "六", "七", "八", "九"];
+    const UNITS: [&str; 4] = ["", "十", "百", "千"];
+    const GROUPS: [&str; 5] = ["", "万", "億", "兆", "京"];
+
+    if n == 0 {
+        return DIGITS[0].to_string();
+    }
+
+    let mut s = String::new();
+    for (g, group) in GROUPS.iter().enumerate().rev() {
+        let part = n / 10_000u64.pow(g as u32) % 10_000;
+        if part == 0 {
+            continue;
+        }
+        for (u, unit) in UNITS.iter().enumerate().rev() {
+            let digit = (part / 10u64.pow(u as u32) % 10) as usize;
+            if digit == 0 {
+                continue;
+            }
+            if digit > 1 || u == 0 {
+                s += DIGITS[digit];
+            }
+            s += unit;
+        }
+        s += group;
+    }
+    s
+}
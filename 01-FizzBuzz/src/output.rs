@@ -0,0 +1,401 @@
+//! ファイルへの出力と圧縮, 中断した出力の再開 (`--output data.txt.zst --resume`)
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use flate2::{read::MultiGzDecoder, write::GzEncoder};
+
+use crate::{names::named, BUFFER_SIZE};
+
+/// 圧縮したファイルの1つのメンバ (gzip) またはフレーム (zstd) に収める, 展開後のおおよそのバイト数
+///
+/// 各メンバは行の区切りで終わります. 中断したファイルを再開するときは,
+/// 最後の完全なメンバより後だけを書き直し, その後に新しいメンバを追加します.
+pub const MEMBER_LEN: u64 = 4 * 1024 * 1024;
+
+/// 出力ファイルの圧縮形式. 拡張子で決まる
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    /// 圧縮しない (既定)
+    #[default]
+    None,
+    /// gzip (`.gz`)
+    Gzip,
+    /// Zstandard (`.zst`, `.zstd`)
+    Zstd,
+}
+
+named!(Compression, "compression", {
+    None => "none",
+    Gzip => "gzip",
+    Zstd => "zstd",
+});
+
+impl Compression {
+    /// ファイル名の拡張子から圧縮形式を決める. 大文字と小文字は区別しない
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        match extension.to_ascii_lowercase().as_str() {
+            "gz" => Self::Gzip,
+            "zst" | "zstd" => Self::Zstd,
+            _ => Self::None,
+        }
+    }
+}
+
+/// 既存の出力ファイルのうち, 改行で終わる完全な行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resume {
+    /// 完全な行の数
+    pub lines: u64,
+    /// 最後の完全な行 (改行を除く)
+    pub last_line: Option<String>,
+    // 完全な行の (展開後の) バイト数
+    len: u64,
+    // 行の区切りで終わる最後の完全なメンバの, ファイル上の終わりの位置と展開後のバイト数
+    member_end: u64,
+    member_len: u64,
+    // ファイルの末尾まで読めて, 途中までの行もなかったか
+    clean: bool,
+}
+
+impl Resume {
+    /// ファイルが完全な行のみからなるか. 途中で切れた行や, 壊れた圧縮データがあればfalse
+    pub fn is_clean(&self) -> bool {
+        self.clean
+    }
+}
+
+/// 既存の出力ファイルを読み, 完全な行の数と最後の行を調べる. ファイルがなければ`None`を返す
+///
+/// 圧縮されたファイルはメンバ (フレーム) ごとに展開して読みます. 出力の中断で末尾が壊れている場合は, 読めたところまでを扱います.
+pub fn scan(path: &Path) -> io::Result<Option<Resume>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let mut scanner = Scanner {
+        resume: Resume {
+            lines: 0,
+            last_line: None,
+            len: 0,
+            member_end: 0,
+            member_len: 0,
+            clean: true,
+        },
+        line: Vec::new(),
+        read: 0,
+        buf: vec![0; BUFFER_SIZE],
+    };
+    let mut input = Counted {
+        inner: BufReader::with_capacity(BUFFER_SIZE, file),
+        pos: 0,
+    };
+    let compression = Compression::from_path(path);
+    if compression == Compression::None {
+        scanner.read(input)?;
+    } else {
+        while !input.fill_buf()?.is_empty() {
+            let complete = match compression {
+                Compression::Gzip => scanner.read(flate2::bufread::GzDecoder::new(&mut input)),
+                _ => scanner.read(zstd::Decoder::with_buffer(&mut input)?.single_frame()),
+            };
+            // 中断された圧縮データの末尾は読めない
+            if complete.is_err() {
+                scanner.resume.clean = false;
+                break;
+            }
+            if scanner.line.is_empty() {
+                scanner.resume.member_end = input.pos;
+                scanner.resume.member_len = scanner.read;
+            }
+        }
+    }
+    if !scanner.line.is_empty() {
+        scanner.resume.clean = false;
+    }
+
+    Ok(Some(scanner.resume))
+}
+
+// 読んだ内容から完全な行を数える
+struct Scanner {
+    resume: Resume,
+    // 最後の改行より後の, 途中までの行
+    line: Vec<u8>,
+    // 読んだ (展開後の) バイト数
+    read: u64,
+    buf: Vec<u8>,
+}
+
+impl Scanner {
+    // 終わりまで読む. エラーの場合も, それまでに読めた分は数えている
+    fn read<R: Read>(&mut self, mut input: R) -> io::Result<()> {
+        loop {
+            let n = match input.read(&mut self.buf) {
+                Ok(0) => return Ok(()),
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            let chunk = &self.buf[..n];
+            let (resume, line) = (&mut self.resume, &mut self.line);
+
+            match chunk.iter().rposition(|&b| b == b'\n') {
+                None => line.extend_from_slice(chunk),
+                Some(end) => {
+                    match chunk[..end].iter().rposition(|&b| b == b'\n') {
+                        Some(begin) => {
+                            line.clear();
+                            line.extend_from_slice(&chunk[begin + 1..end]);
+                        }
+                        None => line.extend_from_slice(&chunk[..end]),
+                    }
+                    resume.last_line = Some(String::from_utf8_lossy(line).into_owned());
+                    resume.lines += chunk.iter().filter(|&&b| b == b'\n').count() as u64;
+                    resume.len = self.read + end as u64 + 1;
+
+                    line.clear();
+                    line.extend_from_slice(&chunk[end + 1..]);
+                }
+            }
+            self.read += n as u64;
+        }
+    }
+}
+
+// 読み進めた位置を数える. 圧縮データのメンバの終わりの位置を求めるのに使う
+struct Counted<R> {
+    inner: R,
+    pos: u64,
+}
+
+impl<R: BufRead> Read for Counted<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for Counted<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.pos += amt as u64;
+    }
+}
+
+/// 標準出力, またはファイルへの出力. ファイルの場合は拡張子に応じて圧縮する
+///
+/// 圧縮形式の終端を書き出すため, 最後に[`Output::finish`]を呼ぶ必要があります.
+pub struct Output {
+    writer: Writer,
+}
+
+enum Writer {
+    Stdout(io::StdoutLock<'static>),
+    Plain(BufWriter<File>),
+    Compressed(Members),
+}
+
+impl Output {
+    /// 標準出力への出力
+    pub fn stdout() -> Self {
+        Self {
+            writer: Writer::Stdout(io::stdout().lock()),
+        }
+    }
+
+    /// ファイルを作成 (既にあれば空に) し, そこへの出力を返す
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let writer = writer(File::create(path)?, Compression::from_path(path))?;
+        Ok(Self { writer })
+    }
+
+    /// [`scan`]で調べたファイルの完全な行の後に続けて書き出す出力を返す
+    ///
+    /// 圧縮しないファイルは途中までの行を切り詰めて追記します.
+    /// 圧縮されたファイルは, 行の区切りで終わる最後の完全なメンバより後を切り詰め,
+    /// そこから完全な行までを書き直してから新しいメンバとして追記します.
+    pub fn append(path: &Path, resume: &Resume) -> io::Result<Self> {
+        let compression = Compression::from_path(path);
+        let mut file = OpenOptions::new().write(true).open(path)?;
+        if compression == Compression::None {
+            file.set_len(resume.len)?;
+            file.seek(SeekFrom::End(0))?;
+            return Ok(Self {
+                writer: Writer::Plain(BufWriter::with_capacity(BUFFER_SIZE, file)),
+            });
+        }
+
+        // 切り詰める前に, 最後の完全なメンバより後の完全な行を読んでおく
+        let mut input = File::open(path)?;
+        input.seek(SeekFrom::Start(resume.member_end))?;
+        let tail_len = resume.len - resume.member_len;
+        let mut tail = Vec::new();
+        decoder(input, compression)?
+            .take(tail_len)
+            .read_to_end(&mut tail)?;
+        if tail.len() as u64 != tail_len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("{} changed while resuming", path.display()),
+            ));
+        }
+
+        file.set_len(resume.member_end)?;
+        file.seek(SeekFrom::End(0))?;
+        let mut output = Self {
+            writer: writer(file, compression)?,
+        };
+        output.write_all(&tail)?;
+
+        Ok(output)
+    }
+
+    /// 残りを書き出し, 圧縮形式の終端を書き出す
+    pub fn finish(self) -> io::Result<()> {
+        let file = match self.writer {
+            Writer::Stdout(mut stdout) => return stdout.flush(),
+            Writer::Plain(writer) => writer,
+            Writer::Compressed(mut members) => members.finish_member()?,
+        };
+        file.into_inner().map_err(io::IntoInnerError::into_error)?;
+        Ok(())
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.writer {
+            Writer::Stdout(w) => w.write(buf),
+            Writer::Plain(w) => w.write(buf),
+            Writer::Compressed(w) => w.write(buf),
+        }
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        match &mut self.writer {
+            Writer::Stdout(w) => w.write_all(buf),
+            Writer::Plain(w) => w.write_all(buf),
+            Writer::Compressed(w) => w.write_all(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.writer {
+            Writer::Stdout(w) => w.flush(),
+            Writer::Plain(w) => w.flush(),
+            Writer::Compressed(w) => w.flush(),
+        }
+    }
+}
+
+// [`MEMBER_LEN`]を越えた後の最初の行の区切りで, 新しいメンバ (フレーム) を始める圧縮の出力
+struct Members {
+    // 前のメンバの終端を書き出せなかった場合は`None`
+    encoder: Option<Encoder>,
+    compression: Compression,
+    // 現在のメンバに書き込んだ (展開後の) バイト数
+    len: u64,
+}
+
+enum Encoder {
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl Encoder {
+    fn new(file: BufWriter<File>, compression: Compression) -> io::Result<Self> {
+        Ok(match compression {
+            Compression::Zstd => Self::Zstd(zstd::Encoder::new(file, 0)?),
+            _ => Self::Gzip(GzEncoder::new(file, flate2::Compression::default())),
+        })
+    }
+
+    fn finish(self) -> io::Result<BufWriter<File>> {
+        match self {
+            Self::Gzip(encoder) => encoder.finish(),
+            Self::Zstd(encoder) => encoder.finish(),
+        }
+    }
+}
+
+impl Members {
+    fn encoder(&mut self) -> io::Result<&mut Encoder> {
+        self.encoder.as_mut().ok_or_else(incomplete)
+    }
+
+    // 現在のメンバの終端を書き出し, 新しいメンバを始める
+    fn next_member(&mut self) -> io::Result<()> {
+        let file = self.finish_member()?;
+        self.encoder = Some(Encoder::new(file, self.compression)?);
+        self.len = 0;
+        Ok(())
+    }
+
+    fn finish_member(&mut self) -> io::Result<BufWriter<File>> {
+        self.encoder.take().ok_or_else(incomplete)?.finish()
+    }
+}
+
+impl Write for Members {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // 上限を越えていれば, 次の改行までを書いてメンバを区切る
+        let end = match buf.iter().position(|&b| b == b'\n') {
+            Some(end) if self.len >= MEMBER_LEN => end + 1,
+            _ => buf.len(),
+        };
+        let n = match self.encoder()? {
+            Encoder::Gzip(w) => w.write(&buf[..end])?,
+            Encoder::Zstd(w) => w.write(&buf[..end])?,
+        };
+        self.len += n as u64;
+        if self.len >= MEMBER_LEN && n > 0 && buf[n - 1] == b'\n' {
+            self.next_member()?;
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.encoder()? {
+            Encoder::Gzip(w) => w.flush(),
+            Encoder::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+// 前のメンバの終端を書き出せず, 続きを書けない
+fn incomplete() -> io::Error {
+    io::Error::other("failed to finish a compressed member")
+}
+
+fn writer(file: File, compression: Compression) -> io::Result<Writer> {
+    let file = BufWriter::with_capacity(BUFFER_SIZE, file);
+    Ok(match compression {
+        Compression::None => Writer::Plain(file),
+        _ => Writer::Compressed(Members {
+            encoder: Some(Encoder::new(file, compression)?),
+            compression,
+            len: 0,
+        }),
+    })
+}
+
+fn decoder(file: File, compression: Compression) -> io::Result<Box<dyn Read>> {
+    let file = BufReader::with_capacity(BUFFER_SIZE, file);
+    Ok(match compression {
+        Compression::None => Box::new(file),
+        Compression::Gzip => Box::new(MultiGzDecoder::new(file)),
+        Compression::Zstd => Box::new(zstd::Decoder::with_buffer(file)?),
+    })
+}
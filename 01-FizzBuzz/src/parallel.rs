@@ -0,0 +1,101 @@
+//! 複数スレッドによるFizzBuzzの生成
+//!
+//! 範囲を一定の個数ごとのチャンクに分割し, 固定数のワーカースレッドで各チャンクをバイト列に変換します.
+//! 変換結果は必ず範囲の先頭から順に書き出すため, 出力は逐次版とバイト単位で一致します.
+
+use std::{
+    io::{self, Write},
+    iter,
+    num::NonZeroUsize,
+    ops::RangeInclusive,
+    panic,
+    sync::mpsc,
+    thread,
+};
+
+/// 1チャンクあたりの数値の個数
+pub const CHUNK_LEN: u64 = 1 << 20;
+
+/// `range`をチャンクに分割し, `jobs`個のスレッドで`render`した結果を順に`out`へ書き出す
+///
+/// チャンクを書き出している間も, 後続の最大`jobs`個のチャンクを変換し続けます.
+/// 一度に保持するのはそれらと書き出し中のチャンクのみなので, 巨大な範囲でもメモリ使用量は一定です.
+pub fn write_parallel<W, F>(
+    mut out: W,
+    range: RangeInclusive<u64>,
+    jobs: NonZeroUsize,
+    render: F,
+) -> io::Result<()>
+where
+    W: Write,
+    F: Fn(&mut Vec<u8>, RangeInclusive<u64>) -> io::Result<()> + Sync,
+{
+    let (start, end) = range.into_inner();
+    let chunk = |start: u64| start..=start.saturating_add(CHUNK_LEN - 1).min(end);
+    let mut chunks = iter::successors((start <= end).then(|| chunk(start)), |prev| {
+        (*prev.end() < end).then(|| chunk(prev.end() + 1))
+    });
+
+    thread::scope(|s| {
+        // チャンクiはワーカー i % jobs が変換する. 各ワーカーが同時に受け持つのは1チャンクのみ
+        let (mut tasks, mut results, mut handles) = (Vec::new(), Vec::new(), Vec::new());
+        for _ in 0..jobs.get() {
+            let (task_tx, task_rx) = mpsc::channel::<(RangeInclusive<u64>, Vec<u8>)>();
+            let (result_tx, result_rx) = mpsc::sync_channel(1);
+            let render = &render;
+            handles.push(s.spawn(move || {
+                for (chunk, mut buf) in task_rx {
+                    buf.clear();
+                    if result_tx
+                        .send(render(&mut buf, chunk).map(|_| buf))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            }));
+            tasks.push(task_tx);
+            results.push(result_rx);
+        }
+
+        let mut write = || {
+            let mut pending = 0;
+            for (task, chunk) in tasks.iter().zip(&mut chunks) {
+                let _ = task.send((chunk, Vec::new()));
+                pending += 1;
+            }
+
+            // 書き出し済みのチャンクのバッファは, 次のチャンクの変換に使い回す
+            let mut spare = None;
+            for worker in (0..jobs.get()).cycle() {
+                if pending == 0 {
+                    break;
+                }
+                // 受け取れない場合はワーカーがパニックしている. 待ち合わせで伝える
+                let Ok(buf) = results[worker].recv() else {
+                    break;
+                };
+                let buf = buf?;
+                pending -= 1;
+
+                // 先頭のチャンクを書き出している間に, 空いたワーカーへ次のチャンクを渡す
+                if let Some(chunk) = chunks.next() {
+                    let _ = tasks[worker].send((chunk, spare.take().unwrap_or_default()));
+                    pending += 1;
+                }
+                out.write_all(&buf)?;
+                spare = Some(buf);
+            }
+
+            out.flush()
+        };
+        let result = write();
+
+        // ワーカーを終了させてから待ち合わせる
+        drop(tasks);
+        for handle in handles {
+            handle.join().unwrap_or_else(|e| panic::resume_unwind(e));
+        }
+        result
+    })
+}
@@ -0,0 +1,227 @@
+//! プログラムと交互に数える対話型のFizzBuzzゲーム
+//!
+//! 奇数はプログラムが, 偶数はプレイヤーが答えます. 間違えるか制限時間を過ぎるとライフが1つ減り,
+//! ライフがなくなるか, 最後の数値まで数え終えるか, `q`を入力するか, 入力が終わるとゲームを終了します.
+
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, Read, Write},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::Config;
+
+/// ゲームの設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayOptions {
+    /// 最後の数値
+    pub n: u64,
+    /// 間違えられる回数
+    pub lives: u32,
+    /// 1回の回答の制限時間
+    pub timeout: Option<Duration>,
+}
+
+impl Default for PlayOptions {
+    fn default() -> Self {
+        Self {
+            n: 100,
+            lives: 3,
+            timeout: None,
+        }
+    }
+}
+
+/// ゲームの終わり方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEnd {
+    /// 最後の数値まで数え終えた
+    Completed,
+    /// ライフがなくなった
+    GameOver,
+    /// `q`を入力した, または入力が終わった
+    Quit,
+}
+
+/// ゲームの結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+    pub end: GameEnd,
+    /// プレイヤーの手番の数 (時間切れを含む)
+    pub turns: u64,
+    /// 正解した数
+    pub correct: u64,
+    /// 最長の連続正解数
+    pub best_streak: u64,
+    /// 回答までにかかった時間 (時間切れを除く)
+    pub reaction_times: Vec<Duration>,
+}
+
+impl Summary {
+    /// 回答までにかかった時間の平均
+    pub fn average_reaction(&self) -> Option<Duration> {
+        let count = u32::try_from(self.reaction_times.len())
+            .ok()
+            .filter(|&n| n > 0)?;
+        Some(self.reaction_times.iter().sum::<Duration>() / count)
+    }
+
+    /// 回答までにかかった最短の時間
+    pub fn best_reaction(&self) -> Option<Duration> {
+        self.reaction_times.iter().min().copied()
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let end = match self.end {
+            GameEnd::Completed => "completed",
+            GameEnd::GameOver => "game over",
+            GameEnd::Quit => "quit",
+        };
+        writeln!(f, "--- {} ---", end)?;
+
+        let rate = match self.turns {
+            0 => 0.0,
+            turns => self.correct as f64 / turns as f64 * 100.0,
+        };
+        writeln!(f, "score: {}/{} ({:.1}%)", self.correct, self.turns, rate)?;
+        writeln!(f, "best streak: {}", self.best_streak)?;
+        match (self.average_reaction(), self.best_reaction()) {
+            (Some(average), Some(best)) => writeln!(
+                f,
+                "reaction time: average {:.2} s, best {:.2} s",
+                average.as_secs_f64(),
+                best.as_secs_f64()
+            ),
+            _ => writeln!(f, "reaction time: -"),
+        }
+    }
+}
+
+/// `input`から1行ずつプレイヤーの回答を読み, ゲームを進める
+///
+/// 制限時間を扱うため, 入力は別スレッドで読みます. 経過は`out`に書き出し, 結果を返します. (結果は書き出しません)
+/// 時間切れの後に届いた, その手番への回答は次の手番の回答とせずに読み捨てます.
+pub fn play<R, W>(
+    input: R,
+    mut out: W,
+    config: &Config,
+    options: PlayOptions,
+) -> io::Result<Summary>
+where
+    R: Read + Send + 'static,
+    W: Write,
+{
+    // 手番ごとに1行だけ読み, どの手番の回答かを添えて返す
+    let (requests, turns) = mpsc::channel::<u64>();
+    let (sender, answers) = mpsc::channel();
+    thread::spawn(move || {
+        let mut lines = BufReader::new(input).lines();
+        for turn in turns {
+            let Some(line) = lines.next() else { break };
+            if sender.send((turn, line)).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut summary = Summary {
+        end: GameEnd::Completed,
+        turns: 0,
+        correct: 0,
+        best_streak: 0,
+        reaction_times: Vec::new(),
+    };
+    let mut streak = 0;
+    let mut lives = options.lives;
+
+    writeln!(
+        out,
+        "Count from 1 to {} in turns. I say the odd numbers, you say the even ones. (q to quit)",
+        options.n
+    )?;
+    for n in 1..=options.n {
+        let expected = config.value(n);
+        if n % 2 == 1 {
+            writeln!(out, "me: {}", expected)?;
+            continue;
+        }
+
+        write!(out, "you: ")?;
+        out.flush()?;
+        // 入力が終わっていれば送れないが, その場合は回答の受信で分かる
+        let _ = requests.send(n);
+        let started = Instant::now();
+        let answer = loop {
+            let answer = match options.timeout {
+                Some(timeout) => answers.recv_timeout(timeout.saturating_sub(started.elapsed())),
+                None => answers.recv().map_err(mpsc::RecvTimeoutError::from),
+            };
+            match answer {
+                // 時間切れになった手番への遅れた回答は捨てる
+                Ok((turn, _)) if turn != n => continue,
+                answer => break answer.map(|(_, line)| line),
+            }
+        };
+        let elapsed = started.elapsed();
+
+        let answer = match answer {
+            Ok(line) => line?,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                summary.turns += 1;
+                streak = 0;
+                lives = lives.saturating_sub(1);
+                writeln!(out)?;
+                writeln!(out, "  time up: {} is {:?} (lives: {})", n, expected, lives)?;
+                if lives == 0 {
+                    summary.end = GameEnd::GameOver;
+                    break;
+                }
+                continue;
+            }
+            // 入力が終わった
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                writeln!(out)?;
+                summary.end = GameEnd::Quit;
+                break;
+            }
+        };
+        let answer = answer.trim();
+        if answer.eq_ignore_ascii_case("q") {
+            summary.end = GameEnd::Quit;
+            break;
+        }
+
+        summary.turns += 1;
+        summary.reaction_times.push(elapsed);
+        if answer.eq_ignore_ascii_case(&expected) {
+            summary.correct += 1;
+            streak += 1;
+            summary.best_streak = summary.best_streak.max(streak);
+            writeln!(
+                out,
+                "  ok ({:.2} s, streak {})",
+                elapsed.as_secs_f64(),
+                streak
+            )?;
+        } else {
+            streak = 0;
+            lives = lives.saturating_sub(1);
+            writeln!(
+                out,
+                "  wrong: {} is {:?}, not {:?} (lives: {})",
+                n, expected, answer, lives
+            )?;
+            if lives == 0 {
+                summary.end = GameEnd::GameOver;
+                break;
+            }
+        }
+    }
+
+    out.flush()?;
+    Ok(summary)
+}
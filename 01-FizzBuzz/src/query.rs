@@ -0,0 +1,116 @@
+//! 1つの値の参照と, 単語の出現位置の逆引き
+//!
+//! どちらも範囲を先頭から生成せず, 算術的に求めます.
+
+use std::{error::Error, fmt, num::NonZeroU64};
+
+use crate::{classify, divisors, stats_up_to, Integer, StatsError, WordRule};
+
+/// 数値`n`に対して出力される値
+pub fn value_at<N: Integer>(n: N, rules: &[WordRule]) -> String {
+    classify(n, rules).unwrap_or_else(|| n.to_string())
+}
+
+/// [`find_nth`]の失敗理由
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FindError {
+    /// どの数値に対しても, その単語は出力されない
+    NeverAppears(String),
+    /// 算術的に計算できないルールが指定された
+    Stats(StatsError),
+    /// u64の範囲にはその個数だけ現れない
+    OutOfRange,
+}
+
+impl fmt::Display for FindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NeverAppears(word) => {
+                write!(f, "`{}` can never appear under the active rules", word)
+            }
+            Self::Stats(e) => write!(f, "{}", e),
+            Self::OutOfRange => write!(f, "the requested occurrence is beyond {}", u64::MAX),
+        }
+    }
+}
+
+impl Error for FindError {}
+
+impl From<StatsError> for FindError {
+    fn from(e: StatsError) -> Self {
+        Self::Stats(e)
+    }
+}
+
+/// 単語`word`が, いずれかの数値に対して出力されうるか
+///
+/// 単語を構成するルールの組`S`について, `S`の除数の最小公倍数が`S`以外の除数で割り切れなければ,
+/// その最小公倍数自身がちょうど`S`のみに該当するため, 単語は出力されうる.
+pub fn can_appear(word: &str, rules: &[WordRule]) -> Result<bool, StatsError> {
+    let divisors = divisors(rules)?;
+    let subsets = 1usize << rules.len();
+
+    Ok((1..subsets).any(|mask| {
+        let selected = |i: &usize| mask & (1 << i) != 0;
+        let concat: String = (0..rules.len())
+            .filter(selected)
+            .map(|i| rules[i].word.as_str())
+            .collect();
+        if concat != word {
+            return false;
+        }
+
+        // `d`が最小公倍数を割り切る <=> 各除数と`d`の最大公約数の最小公倍数が`d`に等しい
+        (0..rules.len()).filter(|i| !selected(i)).all(|other| {
+            let d = divisors[other];
+            let l = (0..rules.len())
+                .filter(selected)
+                .fold(1, |l, i| lcm(l, gcd(d, divisors[i])));
+            l != d
+        })
+    }))
+}
+
+/// 1以上の数値のうち, `nth`番目に`word`が出力される数値
+///
+/// 1からxまでの出現数を包除原理で求め, 二分探索します.
+pub fn find_nth(word: &str, nth: NonZeroU64, rules: &[WordRule]) -> Result<u64, FindError> {
+    if !can_appear(word, rules)? {
+        return Err(FindError::NeverAppears(word.to_string()));
+    }
+    let count = |x: u64| {
+        stats_up_to(x, rules)
+            .ok()
+            .and_then(|stats| stats.count(word))
+            .unwrap_or(0)
+    };
+
+    let nth = u128::from(nth.get());
+    if count(u64::MAX) < nth {
+        return Err(FindError::OutOfRange);
+    }
+    let (mut lo, mut hi) = (1, u64::MAX);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if count(mid) >= nth {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+
+    Ok(lo)
+}
+
+pub(crate) fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// 引数はいずれも`d`の約数なので, 結果も`d`以下に収まる
+fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
@@ -0,0 +1,319 @@
+use std::{fmt, str::FromStr, sync::Arc};
+
+/// 数値が単語を出力する条件を満たすかを判定するルール
+///
+/// 判定には数値の絶対値を用います. (負の数や0も, 割り切れるかは数学的な定義通りに判定されます)
+pub trait Rule: fmt::Display + Send + Sync {
+    fn matches(&self, n: u64) -> bool;
+
+    /// 割り切れるかのみで判定するルールの場合は, その除数
+    ///
+    /// 集計や逆引きなど, 周期性を利用して算術的に計算する機能はこの除数を用います.
+    fn divisor(&self) -> Option<u64> {
+        None
+    }
+
+    /// u64を越える数値 (10進数の文字列) に対する判定. 判定できないルールは`None`を返す
+    fn matches_decimal(&self, digits: &str) -> Option<bool> {
+        let _ = digits;
+        None
+    }
+}
+
+/// 指定の数で割り切れる
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DivisibleBy(pub u64);
+
+impl Rule for DivisibleBy {
+    fn matches(&self, n: u64) -> bool {
+        n.is_multiple_of(self.0)
+    }
+
+    fn divisor(&self) -> Option<u64> {
+        Some(self.0)
+    }
+}
+
+impl fmt::Display for DivisibleBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// 10進表記に指定の数字 (0から9) を含む
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContainsDigit(pub u8);
+
+impl Rule for ContainsDigit {
+    fn matches(&self, n: u64) -> bool {
+        let mut n = n;
+        loop {
+            if n % 10 == u64::from(self.0) {
+                return true;
+            }
+            n /= 10;
+            if n == 0 {
+                return false;
+            }
+        }
+    }
+
+    fn matches_decimal(&self, digits: &str) -> Option<bool> {
+        Some(digits.bytes().any(|d| d == b'0' + self.0))
+    }
+}
+
+impl fmt::Display for ContainsDigit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "contains:{}", self.0)
+    }
+}
+
+/// `radix`進表記に指定の数字を含む
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContainsDigitIn {
+    pub digit: u32,
+    pub radix: u32,
+}
+
+impl Rule for ContainsDigitIn {
+    fn matches(&self, n: u64) -> bool {
+        let radix = u64::from(self.radix);
+        let mut n = n;
+        loop {
+            if n % radix == u64::from(self.digit) {
+                return true;
+            }
+            n /= radix;
+            if n == 0 {
+                return false;
+            }
+        }
+    }
+}
+
+impl fmt::Display for ContainsDigitIn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digit = char::from_digit(self.digit, self.radix).unwrap_or('?');
+        write!(f, "contains:{}", digit)
+    }
+}
+
+/// 素数である
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IsPrime;
+
+impl Rule for IsPrime {
+    // u64の範囲で決定的なMiller-Rabin法 (底は最初の12個の素数)
+    fn matches(&self, n: u64) -> bool {
+        const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+        if n < 2 {
+            return false;
+        }
+        if let Some(&p) = BASES.iter().find(|&&p| n.is_multiple_of(p)) {
+            return n == p;
+        }
+
+        let mul = |a: u64, b: u64| (u128::from(a) * u128::from(b) % u128::from(n)) as u64;
+        let pow = |mut base: u64, mut exp: u64| {
+            let mut result = 1;
+            while exp > 0 {
+                if exp & 1 == 1 {
+                    result = mul(result, base);
+                }
+                base = mul(base, base);
+                exp >>= 1;
+            }
+            result
+        };
+
+        let s = (n - 1).trailing_zeros();
+        let d = (n - 1) >> s;
+        BASES.iter().all(|&a| {
+            let mut x = pow(a, d);
+            if x == 1 || x == n - 1 {
+                return true;
+            }
+            (1..s).any(|_| {
+                x = mul(x, x);
+                x == n - 1
+            })
+        })
+    }
+}
+
+impl fmt::Display for IsPrime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("prime")
+    }
+}
+
+/// 任意の判定関数
+pub struct Custom(pub Box<dyn Fn(u64) -> bool + Send + Sync>);
+
+impl Custom {
+    pub fn new<F: Fn(u64) -> bool + Send + Sync + 'static>(f: F) -> Self {
+        Self(Box::new(f))
+    }
+}
+
+impl Rule for Custom {
+    fn matches(&self, n: u64) -> bool {
+        (self.0)(n)
+    }
+}
+
+impl fmt::Display for Custom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("custom")
+    }
+}
+
+/// ルールと, 該当した場合に出力する単語の組
+#[derive(Clone)]
+pub struct WordRule {
+    pub rule: Arc<dyn Rule>,
+    pub word: String,
+}
+
+impl WordRule {
+    pub fn new<R: Rule + 'static, S: Into<String>>(rule: R, word: S) -> Self {
+        Self {
+            rule: Arc::new(rule),
+            word: word.into(),
+        }
+    }
+
+    /// 通常のFizzBuzzのルール (3→Fizz, 5→Buzz)
+    pub fn classic() -> Vec<Self> {
+        vec![
+            Self::new(DivisibleBy(3), "Fizz"),
+            Self::new(DivisibleBy(5), "Buzz"),
+        ]
+    }
+
+    /// `rules`が通常のFizzBuzzのルールと同一か
+    pub fn is_classic(rules: &[Self]) -> bool {
+        let classic = Self::classic();
+        rules.len() == classic.len()
+            && rules
+                .iter()
+                .zip(&classic)
+                .all(|(a, b)| a.word == b.word && a.divisor() == b.divisor())
+    }
+
+    pub fn matches<N: Integer>(&self, n: N) -> bool {
+        self.rule.matches(n.magnitude())
+    }
+
+    pub fn divisor(&self) -> Option<u64> {
+        self.rule.divisor()
+    }
+
+    /// `=`の左側の数値と数字を`radix`進数 (2から36) として解析する. `ff=Eff`, `contains:f=Eff`など
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Self, String> {
+        let (spec, word) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected RULE=WORD, got `{}`", s))?;
+        if word.is_empty() {
+            return Err("word must not be empty".to_string());
+        }
+
+        let spec = spec.trim();
+        if spec == "prime" {
+            return Ok(Self::new(IsPrime, word));
+        }
+        if let Some(digit) = spec.strip_prefix("contains:") {
+            let mut chars = digit.chars();
+            return match (chars.next().and_then(|c| c.to_digit(radix)), chars.next()) {
+                (Some(d), None) if radix == 10 => Ok(Self::new(ContainsDigit(d as u8), word)),
+                (Some(d), None) => Ok(Self::new(ContainsDigitIn { digit: d, radix }, word)),
+                _ => Err(format!(
+                    "invalid digit `{}`: expected 0-{}",
+                    digit,
+                    char::from_digit(radix - 1, radix).expect("radix is 2..=36")
+                )),
+            };
+        }
+
+        let divisor = spec.strip_prefix("div:").unwrap_or(spec);
+        let divisor = u64::from_str_radix(divisor, radix)
+            .map_err(|e| format!("invalid rule `{}`: {}", spec, e))?;
+        if divisor == 0 {
+            return Err("divisor must be greater than 0".to_string());
+        }
+
+        Ok(Self::new(DivisibleBy(divisor), word))
+    }
+}
+
+/// `3=Fizz`, `contains:3=Fizz`, `prime=Prime` 形式の文字列を解析する
+///
+/// `=`の左側は次のいずれかです.
+///
+/// - `3`, `div:3` : 3で割り切れる
+/// - `contains:3` : 10進表記に3を含む
+/// - `prime` : 素数である
+impl FromStr for WordRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_radix(s, 10)
+    }
+}
+
+impl fmt::Display for WordRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.rule, self.word)
+    }
+}
+
+impl fmt::Debug for WordRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WordRule")
+            .field("rule", &self.rule.to_string())
+            .field("word", &self.word)
+            .finish()
+    }
+}
+
+/// ルールによる判定の対象となる整数型
+pub trait Integer: Copy + fmt::Display {
+    /// 判定に用いる絶対値
+    fn magnitude(self) -> u64;
+
+    fn is_negative(self) -> bool {
+        false
+    }
+}
+
+impl Integer for u64 {
+    fn magnitude(self) -> u64 {
+        self
+    }
+}
+
+impl Integer for i64 {
+    fn magnitude(self) -> u64 {
+        self.unsigned_abs()
+    }
+
+    fn is_negative(self) -> bool {
+        self < 0
+    }
+}
+
+/// 数値`n`に対し, 該当したルールの単語をすべて指定順に連結して返す
+/// どのルールにも該当しない場合は`None`を返す
+pub fn classify<N: Integer>(n: N, rules: &[WordRule]) -> Option<String> {
+    let mut s = String::new();
+    for rule in rules.iter().filter(|rule| rule.matches(n)) {
+        s += &rule.word;
+    }
+
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
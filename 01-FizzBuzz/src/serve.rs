@@ -0,0 +1,429 @@
+//! FizzBuzzを返すHTTPサーバ (標準ライブラリの`TcpListener`のみを使う)
+//!
+//! - `GET /fizzbuzz?from=1&to=100&format=json` : 範囲内の値. `from`の既定値は1, `format`はtext (既定), json, ndjson, csv, table
+//! - `GET /fizzbuzz/{n}` : 1つの数値に対する値. `?format=json`で`{"n":15,"value":"FizzBuzz"}`を返す
+//!
+//! 範囲の応答は生成しながらチャンク形式 (`Transfer-Encoding: chunked`) で送ります.
+//! 1つの接続につき1つの要求を扱い, 応答後に接続を閉じます. 接続は固定数のワーカースレッドで順に扱います.
+
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
+    net::{TcpListener, TcpStream},
+    num::{NonZeroU64, NonZeroUsize},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use crate::{big::Number, write_formatted, write_values, Config, Format, RecordWriter, Span};
+
+/// 要求行とヘッダの合計の上限
+const MAX_HEADER_LEN: u64 = 8 * 1024;
+
+/// 要求を待つ時間の上限
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 応答の書き込みが進まない場合に待つ時間の上限
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// サーバの設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServeOptions {
+    /// 1回の要求で返せる値の個数の上限
+    pub max_range: u64,
+    /// 同時に応答する接続の数 (ワーカースレッドの数)
+    pub max_connections: NonZeroUsize,
+}
+
+impl Default for ServeOptions {
+    fn default() -> Self {
+        Self {
+            max_range: 1_000_000,
+            max_connections: NonZeroUsize::new(64).unwrap(),
+        }
+    }
+}
+
+/// `listener`で接続を待ち, `options.max_connections`個のワーカースレッドで応答する
+///
+/// すべてのワーカーが応答中で, 待ち行列 (ワーカーと同じ数) も埋まっている間は新しい接続を受け付けません.
+/// 一時的な接続の失敗は無視し, それ以外の`accept`の失敗でのみ戻ります.
+pub fn serve(listener: TcpListener, config: Config, options: ServeOptions) -> io::Result<()> {
+    let workers = options.max_connections.get();
+    let (sender, receiver) = mpsc::sync_channel::<TcpStream>(workers);
+    let receiver = Arc::new(Mutex::new(receiver));
+    let config = Arc::new(config);
+    for _ in 0..workers {
+        let receiver = Arc::clone(&receiver);
+        let config = Arc::clone(&config);
+        thread::spawn(move || loop {
+            let stream = receiver.lock().unwrap().recv();
+            let Ok(stream) = stream else {
+                return;
+            };
+            // 応答中に切断されても, サーバは続ける
+            let _ = handle(stream, &config, options);
+        });
+    }
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) if is_transient(&e) => continue,
+            Err(e) => return Err(e),
+        };
+
+        sender
+            .send(stream)
+            .map_err(|_| io::Error::other("all worker threads have stopped"))?;
+    }
+
+    Ok(())
+}
+
+fn is_transient(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::Interrupted
+            | io::ErrorKind::WouldBlock
+    )
+}
+
+// 1つの接続の要求を読み, 応答を書き出す
+fn handle(stream: TcpStream, config: &Config, options: ServeOptions) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let mut out = BufWriter::new(&stream);
+
+    let result = read_request(BufReader::new(&stream)).and_then(|request| route(&request, options));
+    match result {
+        Ok(Route::Range(span, format)) => {
+            write_head(&mut out, 200, "OK", content_type(format), None)?;
+            let mut body = Chunked(out);
+            if config.is_plain() {
+                write_formatted(&mut body, span, &config.rules, format)?;
+            } else {
+                write_values(&mut body, span, format, config.value_width(), |n| {
+                    config.value(n)
+                })?;
+            }
+            body.finish()
+        }
+        Ok(Route::Value(n, format)) => match n.value(config) {
+            Ok(value) => {
+                // 1つの値はJSONの配列ではなくオブジェクトで返す
+                let record = if format == Format::Json {
+                    Format::Ndjson
+                } else {
+                    format
+                };
+                let mut body = Vec::new();
+                let mut writer =
+                    RecordWriter::with_width(&mut body, record, n.to_string().len(), 0)?;
+                writer.write(&n, &value)?;
+                writer.finish()?;
+
+                write_head(&mut out, 200, "OK", content_type(format), Some(body.len()))?;
+                out.write_all(&body)?;
+                out.flush()
+            }
+            Err(message) => write_error(out, HttpError::bad_request(message)),
+        },
+        Err(e) => write_error(out, e),
+    }
+}
+
+/// 要求のうち, 応答に必要な部分
+#[derive(Debug)]
+struct Request {
+    method: String,
+    target: String,
+}
+
+/// 要求に対する応答の内容
+enum Route {
+    Range(Span, Format),
+    Value(Number, Format),
+}
+
+/// 正常に応答できない要求
+#[derive(Debug)]
+struct HttpError {
+    status: u16,
+    reason: &'static str,
+    message: String,
+}
+
+impl HttpError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: 400,
+            reason: "Bad Request",
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+// 要求行とヘッダを読む. 本文は扱わない
+fn read_request<R: Read>(input: BufReader<R>) -> Result<Request, HttpError> {
+    let mut input = input.take(MAX_HEADER_LEN);
+    let mut line = String::new();
+    let read_line = |input: &mut io::Take<BufReader<R>>, line: &mut String| {
+        line.clear();
+        match input.read_line(line) {
+            Ok(0) => Err(HttpError::bad_request("incomplete request")),
+            Ok(_) if !line.ends_with('\n') => {
+                Err(HttpError::bad_request("request header is too large"))
+            }
+            Ok(_) => Ok(()),
+            Err(e) => Err(HttpError::bad_request(format!(
+                "failed to read request: {}",
+                e
+            ))),
+        }
+    };
+
+    read_line(&mut input, &mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target), Some(version), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(HttpError::bad_request("malformed request line"));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(HttpError::bad_request(format!(
+            "unsupported version `{}`",
+            version
+        )));
+    }
+    let request = Request {
+        method: method.to_string(),
+        target: target.to_string(),
+    };
+
+    // 空行までのヘッダは読み捨てる
+    loop {
+        read_line(&mut input, &mut line)?;
+        if line.trim_end().is_empty() {
+            return Ok(request);
+        }
+    }
+}
+
+fn route(request: &Request, options: ServeOptions) -> Result<Route, HttpError> {
+    let (path, query) = request
+        .target
+        .split_once('?')
+        .unwrap_or((&request.target, ""));
+    let path = path.strip_prefix("/fizzbuzz");
+
+    let not_found = || HttpError {
+        status: 404,
+        reason: "Not Found",
+        message: format!("no such resource `{}`", request.target),
+    };
+    let n = match path {
+        Some("" | "/") => None,
+        Some(n) => match n.strip_prefix('/') {
+            Some(n) if !n.contains('/') => Some(percent_decode(n, false)?),
+            _ => return Err(not_found()),
+        },
+        None => return Err(not_found()),
+    };
+    if request.method != "GET" {
+        return Err(HttpError {
+            status: 405,
+            reason: "Method Not Allowed",
+            message: format!("method `{}` is not allowed (use GET)", request.method),
+        });
+    }
+
+    let params = Params::parse(
+        query,
+        if n.is_some() {
+            &["format"]
+        } else {
+            &["from", "to", "format"]
+        },
+    )?;
+    let format = match params.get("format") {
+        Some(format) => format.parse().map_err(HttpError::bad_request)?,
+        None => Format::Text,
+    };
+
+    if let Some(n) = n {
+        let n = Number::parse(&n)
+            .ok_or_else(|| HttpError::bad_request(format!("{:?} is not an integer", n)))?;
+        return Ok(Route::Value(n, format));
+    }
+
+    let from = params.integer("from")?.unwrap_or(1);
+    let to = params
+        .integer("to")?
+        .ok_or_else(|| HttpError::bad_request("missing parameter `to`"))?;
+    let len = from.abs_diff(to) as u128 + 1;
+    if len > u128::from(options.max_range) {
+        return Err(HttpError::bad_request(format!(
+            "range is too large: {} values (must be at most {})",
+            len, options.max_range
+        )));
+    }
+
+    Ok(Route::Range(Span::new(from, to, NonZeroU64::MIN), format))
+}
+
+/// クエリ文字列の値 (`%XX`と`+`を展開したもの)
+struct Params(Vec<(String, String)>);
+
+impl Params {
+    fn parse(query: &str, known: &[&str]) -> Result<Self, HttpError> {
+        let mut params: Vec<(String, String)> = Vec::new();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let (key, value) = (percent_decode(key, true)?, percent_decode(value, true)?);
+            if !known.contains(&key.as_str()) {
+                return Err(HttpError::bad_request(format!(
+                    "unknown parameter `{}` (expected {})",
+                    key,
+                    known.join(", ")
+                )));
+            }
+            if params.iter().any(|(k, _)| *k == key) {
+                return Err(HttpError::bad_request(format!(
+                    "duplicate parameter `{}`",
+                    key
+                )));
+            }
+            params.push((key, value));
+        }
+
+        Ok(Self(params))
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn integer(&self, key: &str) -> Result<Option<i64>, HttpError> {
+        self.get(key)
+            .map(|value| {
+                value.parse().map_err(|_| {
+                    HttpError::bad_request(format!(
+                        "invalid `{}`: {:?} is not an integer within {}..={}",
+                        key,
+                        value,
+                        i64::MIN,
+                        i64::MAX
+                    ))
+                })
+            })
+            .transpose()
+    }
+}
+
+// `%XX`を展開する. クエリ文字列 (`query`) では`+`を空白とする
+fn percent_decode(s: &str, query: bool) -> Result<String, HttpError> {
+    let invalid = || HttpError::bad_request(format!("invalid percent-encoding in `{}`", s));
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        rest = tail;
+        match b {
+            b'%' => {
+                let hex = rest
+                    .get(..2)
+                    .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit));
+                let hex =
+                    std::str::from_utf8(hex.ok_or_else(invalid)?).expect("hex digits are ASCII");
+                bytes.push(u8::from_str_radix(hex, 16).expect("two hex digits fit in u8"));
+                rest = &rest[2..];
+            }
+            b'+' if query => bytes.push(b' '),
+            b => bytes.push(b),
+        }
+    }
+
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+fn content_type(format: Format) -> &'static str {
+    match format {
+        Format::Text | Format::Table => "text/plain; charset=utf-8",
+        Format::Json => "application/json",
+        Format::Ndjson => "application/x-ndjson",
+        Format::Csv => "text/csv; charset=utf-8",
+    }
+}
+
+// 状態行とヘッダを書き出す. `content_length`がない場合はチャンク形式で送る
+fn write_head<W: Write>(
+    out: &mut W,
+    status: u16,
+    reason: &str,
+    content_type: &str,
+    content_length: Option<usize>,
+) -> io::Result<()> {
+    write!(out, "HTTP/1.1 {} {}\r\n", status, reason)?;
+    write!(out, "Content-Type: {}\r\n", content_type)?;
+    if status == 405 {
+        write!(out, "Allow: GET\r\n")?;
+    }
+    match content_length {
+        Some(len) => write!(out, "Content-Length: {}\r\n", len)?,
+        None => write!(out, "Transfer-Encoding: chunked\r\n")?,
+    }
+    write!(out, "Connection: close\r\n\r\n")
+}
+
+fn write_error<W: Write>(mut out: W, error: HttpError) -> io::Result<()> {
+    let body = format!("error: {}\n", error);
+    write_head(
+        &mut out,
+        error.status,
+        error.reason,
+        "text/plain; charset=utf-8",
+        Some(body.len()),
+    )?;
+    out.write_all(body.as_bytes())?;
+    out.flush()
+}
+
+/// 書き込みを1つずつチャンクとして送る出力
+struct Chunked<W: Write>(W);
+
+impl<W: Write> Chunked<W> {
+    /// 終端のチャンクを送る
+    fn finish(mut self) -> io::Result<()> {
+        self.0.write_all(b"0\r\n\r\n")?;
+        self.0.flush()
+    }
+}
+
+impl<W: Write> Write for Chunked<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // 長さ0のチャンクは終端を表すため送らない
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        write!(self.0, "{:x}\r\n", buf.len())?;
+        self.0.write_all(buf)?;
+        self.0.write_all(b"\r\n")?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
@@ -0,0 +1,138 @@
+//! 負の数や降順を含む任意の範囲
+
+use std::{fmt, iter::FusedIterator, num::NonZeroU64};
+
+/// `from`から`to`まで`step`ずつ進む範囲
+///
+/// `from > to`の場合は降順になります. `to`を越える値は生成しないため, 範囲の端でも桁あふれしません.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub from: i64,
+    pub to: i64,
+    pub step: NonZeroU64,
+}
+
+impl Span {
+    pub fn new(from: i64, to: i64, step: NonZeroU64) -> Self {
+        Self { from, to, step }
+    }
+
+    pub fn is_descending(&self) -> bool {
+        self.from > self.to
+    }
+
+    /// 範囲内で実際に到達する最後の値
+    pub fn last(&self) -> i64 {
+        let distance = self.from.abs_diff(self.to);
+        let offset = distance - distance % self.step.get();
+        if self.is_descending() {
+            self.from.wrapping_sub_unsigned(offset)
+        } else {
+            self.from.wrapping_add_unsigned(offset)
+        }
+    }
+
+    /// 範囲の値の個数 (`from`自身を含むため, 常に1以上)
+    pub fn count(&self) -> u64 {
+        self.from.abs_diff(self.to) / self.step.get() + 1
+    }
+
+    /// `i`番目 (0始まり) の値. 範囲を越える場合は`None`
+    pub fn get(&self, i: u64) -> Option<i64> {
+        if i >= self.count() {
+            return None;
+        }
+        let offset = i * self.step.get();
+        Some(if self.is_descending() {
+            self.from.wrapping_sub_unsigned(offset)
+        } else {
+            self.from.wrapping_add_unsigned(offset)
+        })
+    }
+}
+
+impl IntoIterator for Span {
+    type Item = i64;
+    type IntoIter = SpanIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        SpanIter {
+            front: self.from,
+            back: self.last(),
+            step: self.step.get(),
+            descending: self.is_descending(),
+            done: false,
+        }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..={} (step {})", self.from, self.to, self.step)
+    }
+}
+
+/// [`Span`]のイテレータ
+#[derive(Debug, Clone)]
+pub struct SpanIter {
+    front: i64,
+    back: i64,
+    step: u64,
+    descending: bool,
+    done: bool,
+}
+
+impl SpanIter {
+    // `front`と`back`はどちらも範囲内の値なので, 互いに近づく方向への移動は桁あふれしない
+    fn forward(&self, n: i64) -> i64 {
+        if self.descending {
+            n.wrapping_sub_unsigned(self.step)
+        } else {
+            n.wrapping_add_unsigned(self.step)
+        }
+    }
+
+    fn backward(&self, n: i64) -> i64 {
+        if self.descending {
+            n.wrapping_add_unsigned(self.step)
+        } else {
+            n.wrapping_sub_unsigned(self.step)
+        }
+    }
+}
+
+impl Iterator for SpanIter {
+    type Item = i64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let n = self.front;
+        if self.front == self.back {
+            self.done = true;
+        } else {
+            self.front = self.forward(self.front);
+        }
+
+        Some(n)
+    }
+}
+
+impl DoubleEndedIterator for SpanIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let n = self.back;
+        if self.front == self.back {
+            self.done = true;
+        } else {
+            self.back = self.backward(self.back);
+        }
+
+        Some(n)
+    }
+}
+
+impl FusedIterator for SpanIter {}
@@ -0,0 +1,269 @@
+//! 範囲内の各単語の出現数の集計
+//!
+//! 数値を1つずつ判定するのではなく, 包除原理により閉じた式で求めるため, 巨大な範囲でも即座に計算できます.
+
+use std::{error::Error, fmt, num::NonZeroU64};
+
+use crate::{Span, WordRule};
+
+/// 集計できるルールの最大数 (組み合わせの数が`2^MAX_RULES`になるため)
+pub const MAX_RULES: usize = 16;
+
+/// 等差数列`first, first + step, ...`の先頭`count`個
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progression {
+    pub first: i128,
+    pub step: u128,
+    pub count: u128,
+}
+
+impl Progression {
+    pub fn new(first: i128, step: NonZeroU64, count: u128) -> Self {
+        Self {
+            first,
+            step: u128::from(step.get()),
+            count,
+        }
+    }
+
+    /// 1から`n`まで
+    pub fn up_to(n: u64) -> Self {
+        Self {
+            first: 1,
+            step: 1,
+            count: u128::from(n),
+        }
+    }
+}
+
+impl From<Span> for Progression {
+    // 降順の範囲も, 割り切れるかどうかの集計では同じ値の集合を昇順に並べたものと等しい
+    fn from(span: Span) -> Self {
+        let step = span.step.get();
+        Self {
+            first: i128::from(span.from.min(span.last())),
+            step: u128::from(step),
+            count: u128::from(span.from.abs_diff(span.to) / step) + 1,
+        }
+    }
+}
+
+/// 各単語と, どのルールにも該当しない数値の個数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stats {
+    /// 出力される単語ごとの個数. 該当するルールの少ない組み合わせから順に並ぶ
+    pub words: Vec<(String, u128)>,
+    /// 数値のまま出力される個数
+    pub numbers: u128,
+}
+
+impl Stats {
+    pub fn count(&self, word: &str) -> Option<u128> {
+        self.words
+            .iter()
+            .find(|(w, _)| w == word)
+            .map(|&(_, count)| count)
+    }
+
+    pub fn total(&self) -> u128 {
+        self.words.iter().map(|&(_, count)| count).sum::<u128>() + self.numbers
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (word, count) in &self.words {
+            writeln!(f, "{}: {}", word, count)?;
+        }
+        writeln!(f, "Number: {}", self.numbers)
+    }
+}
+
+/// 算術的に集計できないルールが指定された場合のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatsError {
+    /// ルールが[`MAX_RULES`]個を越えている
+    TooManyRules,
+    /// 割り切れるかのみで判定するルールではない
+    Unsupported(String),
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManyRules => write!(f, "at most {} rules are supported", MAX_RULES),
+            Self::Unsupported(rule) => {
+                write!(f, "rule `{}` is not a divisibility rule", rule)
+            }
+        }
+    }
+}
+
+impl Error for StatsError {}
+
+/// 各ルールの除数. 割り切れるかのみで判定するルールでなければエラーとなる
+pub fn divisors(rules: &[WordRule]) -> Result<Vec<u64>, StatsError> {
+    if rules.len() > MAX_RULES {
+        return Err(StatsError::TooManyRules);
+    }
+    rules
+        .iter()
+        .map(|rule| {
+            rule.divisor()
+                .ok_or_else(|| StatsError::Unsupported(rule.to_string()))
+        })
+        .collect()
+}
+
+/// `values`に含まれる各単語の個数を包除原理で求める
+pub fn stats(values: Progression, rules: &[WordRule]) -> Result<Stats, StatsError> {
+    let divisors = divisors(rules)?;
+    let subsets = 1usize << rules.len();
+
+    // exact[mask]: はじめは`mask`のすべての除数で割り切れる個数. 上位集合を引いていくと,
+    // ちょうど`mask`のルールのみに該当する個数になる
+    let cap = lcm_cap(values);
+    let mut lcm = vec![1u128; subsets];
+    let mut exact = vec![0u128; subsets];
+    for mask in 0..subsets {
+        if mask > 0 {
+            let low = mask.trailing_zeros() as usize;
+            lcm[mask] = lcm_capped(lcm[mask & (mask - 1)], u128::from(divisors[low]), cap);
+        }
+        exact[mask] = multiples(values, lcm[mask]);
+    }
+    for bit in 0..rules.len() {
+        for mask in (0..subsets).filter(|mask| mask & (1 << bit) == 0) {
+            exact[mask] = exact[mask]
+                .checked_sub(exact[mask | (1 << bit)])
+                .expect("a superset of rules never matches more values");
+        }
+    }
+
+    let mut masks: Vec<_> = (1..subsets).collect();
+    masks.sort_by_key(|mask| mask.count_ones());
+
+    let mut words: Vec<(String, u128)> = Vec::new();
+    for mask in masks {
+        let word: String = rules
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| mask & (1 << i) != 0)
+            .map(|(_, rule)| rule.word.as_str())
+            .collect();
+        let count = exact[mask];
+        match words.iter_mut().find(|(w, _)| *w == word) {
+            Some((_, total)) => *total += count,
+            None => words.push((word, count)),
+        }
+    }
+
+    Ok(Stats {
+        words,
+        numbers: exact[0],
+    })
+}
+
+/// 1から`n`までの集計
+pub fn stats_up_to(n: u64, rules: &[WordRule]) -> Result<Stats, StatsError> {
+    stats(Progression::up_to(n), rules)
+}
+
+// 数列の値の絶対値の最大値 + 1. これ以上の最小公倍数は, 数列に0以外の倍数を持たない.
+// 以降の計算で桁あふれしないよう, そのような最小公倍数はすべてこの値に丸める
+fn lcm_cap(values: Progression) -> u128 {
+    let extent = values.count.saturating_sub(1).saturating_mul(values.step);
+    let first = values.first.unsigned_abs();
+    let last = if values.first < 0 {
+        extent.abs_diff(first)
+    } else {
+        first.saturating_add(extent)
+    };
+    first.max(last).saturating_add(1)
+}
+
+fn lcm_capped(a: u128, b: u128, cap: u128) -> u128 {
+    (a / gcd(a, b))
+        .checked_mul(b)
+        .map_or(cap, |lcm| lcm.min(cap))
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// `values`のうち`l`の倍数の個数. `first + k * step ≡ 0 (mod l)`となる`k`を数える
+fn multiples(values: Progression, l: u128) -> u128 {
+    let Progression { first, step, count } = values;
+    let a = step % l;
+    // `-first mod l`
+    let r = first.unsigned_abs() % l;
+    let b = if first < 0 || r == 0 { r } else { l - r };
+
+    let g = gcd(l, a);
+    if !b.is_multiple_of(g) {
+        return 0;
+    }
+    let period = l / g;
+    let k0 = mul_mod(b / g, inverse(a / g % period, period), period);
+
+    if k0 >= count {
+        0
+    } else {
+        (count - 1 - k0) / period + 1
+    }
+}
+
+// `a + b mod m` (`a`, `b`は`m`未満). `m`がu128の上限に近くても桁あふれしない
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+// `a - b mod m` (`a`, `b`は`m`未満)
+fn sub_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= b {
+        a - b
+    } else {
+        m - (b - a)
+    }
+}
+
+// `a * b mod m`. 積が桁あふれする場合は, 加算のみで計算する
+fn mul_mod(a: u128, mut b: u128, m: u128) -> u128 {
+    let mut a = a % m;
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+    let mut result = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+
+    result
+}
+
+// `m`を法とする`a`の逆元 (`a`と`m`は互いに素). `m == 1`の場合は0を返す.
+// 係数は`m`を法として保つため, 負の数を使わない
+fn inverse(a: u128, m: u128) -> u128 {
+    let (mut r0, mut r1) = (m, a);
+    let (mut t0, mut t1) = (0, 1 % m);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, sub_mod(t0, mul_mod(q, t1, m), m));
+    }
+
+    t0
+}
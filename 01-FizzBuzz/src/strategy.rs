@@ -0,0 +1,116 @@
+//! 書き方の異なるFizzBuzzの実装 (`examples/answer.rs`の解答と別解)
+//!
+//! どの実装も同じバッファを介して書き出すため, 速度の差は分岐と文字列の扱い方の差になります.
+//! 既定の出力に使う[`write_fizz_buzz`](crate::write_fizz_buzz)は, これらとは別に数値の整形まで最適化したものです.
+
+use std::{
+    io::{self, BufWriter, Write},
+    ops::RangeInclusive,
+    time::{Duration, Instant},
+};
+
+use crate::{names::named, BUFFER_SIZE};
+
+/// FizzBuzzの実装方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// `(i % 3, i % 5)`の組に対する`match` (既定)
+    #[default]
+    Match,
+    /// `if`, `else if`の連鎖
+    If,
+    /// 該当する単語を`String`に連結する
+    Concat,
+    /// `i % 15`で引く15要素の表
+    Table,
+}
+
+named!(Strategy, "strategy", {
+    Match => "match",
+    If => "if",
+    Concat => "concat",
+    Table => "table",
+});
+
+/// `i % 15`ごとの出力内容. `None`は数値をそのまま出力することを表す
+const TABLE: [Option<&str>; 15] = [
+    Some("FizzBuzz"),
+    None,
+    None,
+    Some("Fizz"),
+    None,
+    Some("Buzz"),
+    Some("Fizz"),
+    None,
+    None,
+    Some("Fizz"),
+    Some("Buzz"),
+    None,
+    Some("Fizz"),
+    None,
+    None,
+];
+
+impl Strategy {
+    /// 範囲内のFizzBuzzを1行ずつ書き出す
+    pub fn write<W: Write>(self, out: W, range: RangeInclusive<u64>) -> io::Result<()> {
+        let mut out = BufWriter::with_capacity(BUFFER_SIZE, out);
+        match self {
+            Self::Match => {
+                for i in range {
+                    match (i % 3, i % 5) {
+                        (0, 0) => writeln!(out, "FizzBuzz")?,
+                        (0, _) => writeln!(out, "Fizz")?,
+                        (_, 0) => writeln!(out, "Buzz")?,
+                        (_, _) => writeln!(out, "{}", i)?,
+                    }
+                }
+            }
+            Self::If => {
+                for i in range {
+                    if i % 3 == 0 && i % 5 == 0 {
+                        writeln!(out, "FizzBuzz")?;
+                    } else if i % 3 == 0 {
+                        writeln!(out, "Fizz")?;
+                    } else if i % 5 == 0 {
+                        writeln!(out, "Buzz")?;
+                    } else {
+                        writeln!(out, "{}", i)?;
+                    }
+                }
+            }
+            Self::Concat => {
+                for i in range {
+                    let mut s = String::new();
+                    if i % 3 == 0 {
+                        s += "Fizz";
+                    }
+                    if i % 5 == 0 {
+                        s += "Buzz";
+                    }
+                    if s.is_empty() {
+                        s += &i.to_string();
+                    }
+                    writeln!(out, "{}", s)?;
+                }
+            }
+            Self::Table => {
+                for i in range {
+                    match TABLE[(i % 15) as usize] {
+                        Some(word) => writeln!(out, "{}", word)?,
+                        None => writeln!(out, "{}", i)?,
+                    }
+                }
+            }
+        }
+
+        out.flush()
+    }
+
+    /// 1からNまでを書き出す (出力は捨てる) のにかかった時間を計測する
+    pub fn bench(self, n: u64) -> io::Result<Duration> {
+        let started = Instant::now();
+        self.write(io::sink(), 1..=n)?;
+        Ok(started.elapsed())
+    }
+}
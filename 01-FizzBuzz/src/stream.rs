@@ -0,0 +1,182 @@
+//! 標準入力などから読んだ整数を1行ずつ判定する変換処理
+//!
+//! `seq 100 200 | fizz-buzz --stdin` のように, 既存のパイプラインの中でルールを使うためのものです.
+//! 入力が途切れて待つ前に出力をフラッシュするため, 流れ続ける入力にも1行ずつ応答します.
+
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
+    num::NonZeroUsize,
+};
+
+use crate::{big::Number, format::Csv, names::named, Config, BUFFER_SIZE};
+
+/// 整数として解析できない行の扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnInvalid {
+    /// そのまま出力する
+    Pass,
+    /// 出力しない
+    Skip,
+    /// 行番号を示して中断する (既定)
+    #[default]
+    Error,
+}
+
+named!(OnInvalid, "action", {
+    Pass => "pass",
+    Skip => "skip",
+    Error => "error",
+});
+
+/// 整数として解析できなかった行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidLine {
+    /// 整数ではない
+    NotInteger { line: u64, text: String },
+    /// 指定の列がない
+    MissingField { line: u64, field: NonZeroUsize },
+}
+
+impl InvalidLine {
+    /// 解析できなかった行番号 (1始まり)
+    pub fn line(&self) -> u64 {
+        match self {
+            Self::NotInteger { line, .. } | Self::MissingField { line, .. } => *line,
+        }
+    }
+}
+
+impl fmt::Display for InvalidLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotInteger { line, text } => {
+                write!(f, "line {}: {:?} is not an integer", line, text)
+            }
+            Self::MissingField { line, field } => {
+                write!(f, "line {}: missing field {}", line, field)
+            }
+        }
+    }
+}
+
+/// `input`を1行ずつ読み, 各行の整数を`config`で判定した値を書き出す
+///
+/// `field`を指定した場合は, 各行をカンマで区切った`field`列目 (1始まり) の整数を判定し, その列だけを値に置き換えます.
+/// 引用符で囲まれた列の中のカンマでは区切りません. 置き換えた値は, 必要な場合のみ引用符で囲みます.
+/// 前後の空白は無視します. u64を越える非負の整数も扱えます.
+///
+/// 解析できない行を`OnInvalid::Error`で中断した場合は, その行を返します. (それまでの出力は書き出し済みです)
+pub fn transform<R, W>(
+    input: R,
+    out: W,
+    config: &Config,
+    field: Option<NonZeroUsize>,
+    on_invalid: OnInvalid,
+) -> io::Result<Result<u64, InvalidLine>>
+where
+    R: Read,
+    W: Write,
+{
+    let mut input = BufReader::with_capacity(BUFFER_SIZE, input);
+    let mut out = BufWriter::with_capacity(BUFFER_SIZE, out);
+    let mut buf = Vec::new();
+    let mut line = 0;
+
+    loop {
+        // 入力を待つ前に, それまでの出力を流す
+        if input.buffer().is_empty() {
+            out.flush()?;
+        }
+
+        buf.clear();
+        if input.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+        line += 1;
+        let bytes = buf.strip_suffix(b"\n").unwrap_or(&buf);
+        let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+
+        // UTF-8でない行は整数ではない
+        let parsed = match std::str::from_utf8(bytes) {
+            Ok(text) => match split_field(text, field) {
+                Some((before, target, after)) => match value(unquote(target.trim()), config)? {
+                    Some(value) if field.is_some() => Ok((before, Csv(&value).to_string(), after)),
+                    Some(value) => Ok((before, value, after)),
+                    None => Err(InvalidLine::NotInteger {
+                        line,
+                        text: text.to_string(),
+                    }),
+                },
+                None => Err(InvalidLine::MissingField {
+                    line,
+                    field: field.expect("only fields can be missing"),
+                }),
+            },
+            Err(_) => Err(InvalidLine::NotInteger {
+                line,
+                text: String::from_utf8_lossy(bytes).into_owned(),
+            }),
+        };
+
+        match (parsed, on_invalid) {
+            (Ok((before, value, after)), _) => writeln!(out, "{}{}{}", before, value, after)?,
+            (Err(_), OnInvalid::Pass) => {
+                out.write_all(bytes)?;
+                out.write_all(b"\n")?;
+            }
+            (Err(_), OnInvalid::Skip) => {}
+            (Err(invalid), OnInvalid::Error) => {
+                out.flush()?;
+                return Ok(Err(invalid));
+            }
+        }
+    }
+
+    out.flush()?;
+    Ok(Ok(line))
+}
+
+// 行を, 対象の列の前, 対象の列, 対象の列の後に分ける. 引用符で囲まれた列の中のカンマでは区切らない
+fn split_field(text: &str, field: Option<NonZeroUsize>) -> Option<(&str, &str, &str)> {
+    let Some(field) = field else {
+        return Some(("", text, ""));
+    };
+
+    let mut start = 0;
+    for _ in 1..field.get() {
+        start = field_end(text, start)? + 1;
+    }
+    let end = field_end(text, start).unwrap_or(text.len());
+    Some((&text[..start], &text[start..end], &text[end..]))
+}
+
+// `start`から始まる列の後のカンマの位置. 最後の列なら`None`
+//
+// 引用符の中の`""`は2回切り替わるため, そのまま引用符の中として扱える.
+fn field_end(text: &str, start: usize) -> Option<usize> {
+    let mut quoted = false;
+    for (i, b) in text[start..].bytes().enumerate() {
+        match b {
+            b'"' => quoted = !quoted,
+            b',' if !quoted => return Some(start + i),
+            _ => {}
+        }
+    }
+    None
+}
+
+// 引用符で囲まれた列の中身. 整数は引用符を含まないため, `""`は戻さない
+fn unquote(s: &str) -> &str {
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .map_or(s, str::trim)
+}
+
+// 整数であれば判定した値を返す
+fn value(s: &str, config: &Config) -> io::Result<Option<String>> {
+    Number::parse(s)
+        .map(|n| n.value(config))
+        .transpose()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
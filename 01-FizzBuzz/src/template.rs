@@ -0,0 +1,124 @@
+//! 1行ごとの出力の書式 (`--template "{n}: {word}"`)
+
+use std::{fmt, str::FromStr};
+
+/// 1行ごとの出力の書式
+///
+/// 次のプレースホルダを使えます. `{`, `}`そのものは`{{`, `}}`と書きます.
+///
+/// - `{n}` : 数値
+/// - `{word}` : 出力する値 (どのルールにも該当しない場合は数値)
+/// - `{index}` : 1から数えた行番号. `{index:4}`のように桁数を指定すると0埋めします
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Number,
+    Word,
+    Index { width: usize },
+}
+
+impl Template {
+    /// 1行分の文字列 (改行を除く) を返す
+    pub fn render<N: fmt::Display>(&self, index: u64, n: N, word: &str) -> String {
+        let mut s = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(literal) => s += literal,
+                Part::Number => s += &n.to_string(),
+                Part::Word => s += word,
+                Part::Index { width } => s += &format!("{:0w$}", index, w = *width),
+            }
+        }
+        s
+    }
+}
+
+/// 書式の誤り
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// 不明なプレースホルダ
+    UnknownPlaceholder(String),
+    /// `{`が閉じられていない
+    Unclosed,
+    /// 対応する`{`のない`}`
+    UnmatchedBrace,
+    /// `{index:N}`の桁数が不正
+    InvalidWidth(String),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownPlaceholder(name) => write!(
+                f,
+                "unknown placeholder `{{{}}}` (expected {{n}}, {{word}} or {{index}})",
+                name
+            ),
+            Self::Unclosed => write!(f, "unclosed `{{` (use `{{{{` for a literal brace)"),
+            Self::UnmatchedBrace => write!(f, "unmatched `}}` (use `}}}}` for a literal brace)"),
+            Self::InvalidWidth(width) => write!(f, "invalid width `{}` in {{index:N}}", width),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl FromStr for Template {
+    type Err = TemplateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = s.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '}' => return Err(TemplateError::UnmatchedBrace),
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(TemplateError::Unclosed),
+                        }
+                    }
+
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(match name.split_once(':') {
+                        None if name == "n" => Part::Number,
+                        None if name == "word" => Part::Word,
+                        None if name == "index" => Part::Index { width: 0 },
+                        Some(("index", width)) => Part::Index {
+                            width: width
+                                .parse()
+                                .map_err(|_| TemplateError::InvalidWidth(width.to_string()))?,
+                        },
+                        _ => return Err(TemplateError::UnknownPlaceholder(name)),
+                    });
+                }
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Ok(Self { parts })
+    }
+}
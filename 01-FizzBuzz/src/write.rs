@@ -0,0 +1,46 @@
+//! 大量の出力を高速に書き出すための処理
+//!
+//! `println!`は1行ごとに標準出力をロックし, 改行のたびにフラッシュするため, 巨大なNでは非常に遅くなります.
+//! ここでは大きなバッファを介して書き込みます. 通常のFizzBuzzの整形は`fizz-buzz-core`の[`Lines`]に任せ,
+//! 数値の10進表現も`to_string()`せずにその場で繰り上げます.
+
+use std::{
+    io::{self, BufWriter, Write},
+    ops::RangeInclusive,
+};
+
+use crate::{classify, Lines, WordRule};
+
+/// 書き込みバッファのサイズ
+pub const BUFFER_SIZE: usize = 1 << 16;
+
+/// 指定範囲の通常のFizzBuzzを1行ずつ書き出す
+///
+/// 整形は[`Lines`]で`BUFFER_SIZE`ずつまとめて行い, そのまま書き出します.
+pub fn write_fizz_buzz<W: Write>(mut out: W, range: RangeInclusive<u64>) -> io::Result<()> {
+    let mut lines = Lines::new(range);
+    let mut buf = vec![0; BUFFER_SIZE];
+    while !lines.is_done() {
+        let len = lines.fill(&mut buf);
+        out.write_all(&buf[..len])?;
+    }
+
+    out.flush()
+}
+
+/// 指定範囲を任意のルールで1行ずつ書き出す
+pub fn write_with_rules<W: Write>(
+    out: W,
+    range: RangeInclusive<u64>,
+    rules: &[WordRule],
+) -> io::Result<()> {
+    let mut out = BufWriter::with_capacity(BUFFER_SIZE, out);
+    for n in range {
+        match classify(n, rules) {
+            Some(word) => writeln!(out, "{}", word)?,
+            None => writeln!(out, "{}", n)?,
+        }
+    }
+
+    out.flush()
+}
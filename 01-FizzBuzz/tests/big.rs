@@ -0,0 +1,125 @@
+use std::num::NonZeroU64;
+
+use fizz_buzz::{
+    write_big, write_fizz_buzz, write_with_rules, Decimal, DivisibleBy, Format, WordRule,
+};
+
+fn big(from: &str, step: u64, count: u64, rules: &[WordRule]) -> String {
+    let mut out = Vec::new();
+    let from = from.parse().unwrap();
+    let step = NonZeroU64::new(step).unwrap();
+    write_big(&mut out, from, step, count, rules, Format::Text).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+// 10進数と累乗の形式が解析できれば可
+#[test]
+fn test_decimal_parse() {
+    let n: Decimal = "10^40".parse().unwrap();
+    assert_eq!(n.to_string(), format!("1{}", "0".repeat(40)));
+    assert_eq!("000123".parse::<Decimal>().unwrap().to_string(), "123");
+    assert_eq!("0".parse::<Decimal>().unwrap().to_u64(), Some(0));
+    assert!("-1".parse::<Decimal>().is_err());
+    assert!("12a".parse::<Decimal>().is_err());
+    assert!("".parse::<Decimal>().is_err());
+}
+
+// 累乗は桁数の上限まで速やかに求まり, それを越えればエラーになれば可
+#[test]
+fn test_decimal_pow() {
+    let pow = |s: &str| s.parse::<Decimal>().map(|n| n.to_string());
+    assert_eq!(pow("2^100").unwrap(), "1267650600228229401496703205376");
+    assert_eq!(pow("3^50").unwrap(), "717897987691852588770249");
+    assert_eq!(pow("20^3").unwrap(), "8000");
+    assert_eq!(pow("0^0").unwrap(), "1");
+    assert_eq!(pow("0^7").unwrap(), "0");
+    assert_eq!(pow("1^4000000000").unwrap(), "1");
+    assert_eq!(pow("18446744073709551615^1").unwrap(), u64::MAX.to_string());
+
+    // 2^64 - 1 の2乗
+    let max = u128::from(u64::MAX);
+    assert_eq!(
+        pow("18446744073709551615^2").unwrap(),
+        (max * max).to_string()
+    );
+
+    let n = pow(&format!("10^{}", Decimal::MAX_POW_DIGITS - 1)).unwrap();
+    assert_eq!(n.len(), Decimal::MAX_POW_DIGITS);
+    // 大きな累乗は, 剰余を繰り返し二乗法で求めた値と比べる
+    let n: Decimal = "7^100000".parse().unwrap();
+    assert_eq!(n.to_string().len(), 84510);
+    for m in [1_000_000_007u64, u64::MAX - 58] {
+        let (mut expected, mut base, mut exp) = (1u128, 7u128, 100_000);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                expected = expected * base % u128::from(m);
+            }
+            base = base * base % u128::from(m);
+            exp >>= 1;
+        }
+        assert_eq!(u128::from(n.rem(m)), expected, "{}", m);
+    }
+
+    assert_eq!(
+        pow("10^2000000").unwrap_err(),
+        "`10^2000000` is too large (must have at most 100000 digits)"
+    );
+    assert!(pow("3^4294967295").is_err());
+}
+
+// 加算と剰余が正しく計算されれば可
+#[test]
+fn test_decimal_arithmetic() {
+    let mut n = Decimal::from(u64::MAX);
+    n.add(1);
+    assert_eq!(n.to_string(), "18446744073709551616");
+    assert_eq!(n.to_u64(), None);
+    n.add(999);
+    assert_eq!(n.to_string(), "18446744073709552615");
+
+    let n: Decimal = "10^40".parse().unwrap();
+    assert_eq!(n.rem(3), 1);
+    assert_eq!(n.rem(7), 4);
+    assert_eq!(n.rem(13), 3);
+    assert_eq!(Decimal::from(u64::MAX - 1).rem(u64::MAX), u64::MAX - 1);
+}
+
+// u64の範囲では, 通常の経路と同じ出力になれば可
+#[test]
+fn test_big_matches_u64() {
+    let mut expected = Vec::new();
+    write_fizz_buzz(&mut expected, 1..=100).unwrap();
+    assert_eq!(
+        big("1", 1, 100, &WordRule::classic()).into_bytes(),
+        expected
+    );
+
+    let mut expected = Vec::new();
+    write_fizz_buzz(&mut expected, u64::MAX - 30..=u64::MAX).unwrap();
+    assert_eq!(
+        big(&(u64::MAX - 30).to_string(), 1, 31, &WordRule::classic()).into_bytes(),
+        expected
+    );
+
+    let rules = vec![
+        WordRule::new(DivisibleBy(2), "Foo"),
+        WordRule::new(DivisibleBy(7), "Bar"),
+        WordRule::new(DivisibleBy(u64::MAX), "Max"),
+    ];
+    let mut expected = Vec::new();
+    write_with_rules(&mut expected, u64::MAX - 30..=u64::MAX, &rules).unwrap();
+    assert_eq!(
+        big(&(u64::MAX - 30).to_string(), 1, 31, &rules).into_bytes(),
+        expected
+    );
+}
+
+// 増分を指定した場合も, 各ルールの余りが正しく更新されれば可
+#[test]
+fn test_big_step() {
+    assert_eq!(
+        big("10^40", 5, 3, &WordRule::classic()),
+        "Buzz\nFizzBuzz\nBuzz\n"
+    );
+    assert_eq!(big("10^40", 1, 0, &WordRule::classic()), "");
+}
@@ -0,0 +1,61 @@
+use fizz_buzz::{check, Mismatch};
+
+const EXPECTED: [&str; 5] = ["1", "2", "Fizz", "4", "Buzz"];
+
+fn run(input: &str) -> Result<u64, Mismatch> {
+    check(input.as_bytes(), EXPECTED).unwrap()
+}
+
+// 完全に一致すれば行数が返れば可
+#[test]
+fn test_check_ok() {
+    assert_eq!(run("1\n2\nFizz\n4\nBuzz\n"), Ok(5));
+    assert_eq!(check("".as_bytes(), [""; 0]).unwrap(), Ok(0));
+}
+
+// 最初に相違のある行と, その内容が返れば可
+#[test]
+fn test_check_line() {
+    let mismatch = run("1\n2\nFizz\nFour\nBuzz\n").unwrap_err();
+    assert_eq!(
+        mismatch,
+        Mismatch::Line {
+            line: 4,
+            expected: "4".to_string(),
+            actual: "Four".to_string(),
+        }
+    );
+    assert_eq!(
+        mismatch.to_string(),
+        r#"line 4: expected "4", found "Four""#
+    );
+}
+
+// 行の過不足が検出されれば可
+#[test]
+fn test_check_missing_and_extra() {
+    assert_eq!(
+        run("1\n2\nFizz\n"),
+        Err(Mismatch::Missing {
+            line: 4,
+            expected: "4".to_string(),
+        })
+    );
+    assert_eq!(
+        run("1\n2\nFizz\n4\nBuzz\n\n"),
+        Err(Mismatch::Extra {
+            line: 6,
+            actual: "".to_string(),
+        })
+    );
+}
+
+// 改行の誤りが検出されれば可
+#[test]
+fn test_check_newlines() {
+    assert_eq!(run("1\r\n2\r\n"), Err(Mismatch::CrLf { line: 1 }));
+    assert_eq!(
+        run("1\n2\nFizz\n4\nBuzz"),
+        Err(Mismatch::NoTrailingNewline { line: 5 })
+    );
+}
@@ -0,0 +1,47 @@
+use fizz_buzz::{ColorChoice, Config};
+
+#[test]
+fn test_enabled() {
+    // (端末か, NO_COLORが設定されているか)
+    let cases = [(true, false), (true, true), (false, false), (false, true)];
+    let enabled = |choice: ColorChoice| cases.map(|(tty, no_color)| choice.enabled(tty, no_color));
+    assert_eq!(enabled(ColorChoice::Auto), [true, false, false, false]);
+    assert_eq!(enabled(ColorChoice::Always), [true; 4]);
+    assert_eq!(enabled(ColorChoice::Never), [false; 4]);
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!("always".parse(), Ok(ColorChoice::Always));
+    assert_eq!("Never".parse(), Ok(ColorChoice::Never));
+    assert_eq!(
+        "sometimes".parse::<ColorChoice>(),
+        Err("unknown color `sometimes` (expected one of: auto, always, never)".to_string())
+    );
+    assert_eq!(ColorChoice::default(), ColorChoice::Auto);
+}
+
+#[test]
+fn test_config_color() {
+    let mut config = Config {
+        color: true,
+        ..Config::default()
+    };
+    assert!(!config.is_plain());
+
+    // 数値はそのまま, 単語はルールごとの色, 複数のルールに該当した単語は太字
+    assert_eq!(config.value(7u64), "7");
+    assert_eq!(config.value(3u64), "\x1b[33mFizz\x1b[0m");
+    assert_eq!(config.value(5u64), "\x1b[36mBuzz\x1b[0m");
+    assert_eq!(
+        config.value(15u64),
+        "\x1b[1;33mFizz\x1b[0m\x1b[1;36mBuzz\x1b[0m"
+    );
+
+    // 区切りには色を付けない
+    config.separator = "-".to_string();
+    assert_eq!(
+        config.value(15u64),
+        "\x1b[1;33mFizz\x1b[0m-\x1b[1;36mBuzz\x1b[0m"
+    );
+}
@@ -0,0 +1,34 @@
+//! 結合テストで共通に使う補助
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// テストごとの一時ディレクトリ. テストの終了時に (失敗した場合も) 削除する
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// `name`はテストバイナリ内で一意な名前
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("fizz-buzz-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    pub fn join(&self, file: &str) -> PathBuf {
+        self.0.join(file)
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
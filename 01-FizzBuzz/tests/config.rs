@@ -0,0 +1,137 @@
+use fizz_buzz::{Config, ConfigError, Decimal};
+use indoc::indoc;
+
+const CONFIG: &str = indoc! {r#"
+    separator = "-"
+    fallback = "({n})"
+
+    [[rules]]
+    divisor = 3
+    word = "Fizz"
+
+    [[rules]]
+    kind = "divisible"
+    divisor = 5
+    word = "Buzz"
+
+    [[rules]]
+    kind = "contains"
+    digit = 7
+    word = "Lucky"
+"#};
+
+fn rule_error(config: Result<Config, ConfigError>) -> (usize, String) {
+    match config {
+        Err(ConfigError::Rule { index, message, .. }) => (index, message),
+        other => panic!("expected a rule error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_toml() {
+    let config = Config::from_toml(CONFIG).unwrap();
+    let values: Vec<_> = (1..=17u64).map(|n| config.value(n)).collect();
+    assert_eq!(
+        values,
+        [
+            "(1)",
+            "(2)",
+            "Fizz",
+            "(4)",
+            "Buzz",
+            "Fizz",
+            "Lucky",
+            "(8)",
+            "Fizz",
+            "Buzz",
+            "(11)",
+            "Fizz",
+            "(13)",
+            "(14)",
+            "Fizz-Buzz",
+            "(16)",
+            "Lucky",
+        ]
+    );
+    assert_eq!(config.value(-15i64), "Fizz-Buzz");
+    assert_eq!(config.value(105u64), "Fizz-Buzz");
+    assert_eq!(config.value(57u64), "Fizz-Lucky");
+
+    let big: Decimal = "10^20".parse().unwrap();
+    assert_eq!(config.value_big(&big).unwrap(), "Buzz");
+    assert!(!config.is_plain());
+}
+
+#[test]
+fn test_json() {
+    let json = r#"{"separator": " ", "rules": [{"divisor": 2, "word": "Foo"}, {"kind": "prime", "word": "Bar"}]}"#;
+    let config = Config::from_json(json).unwrap();
+    let values: Vec<_> = (1..=6u64).map(|n| config.value(n)).collect();
+    assert_eq!(values, ["1", "Foo Bar", "Bar", "Foo", "Bar", "Foo"]);
+}
+
+// ルールの指定がない場合は通常のFizzBuzz
+#[test]
+fn test_default_rules() {
+    let config = Config::from_toml("").unwrap();
+    assert!(config.is_plain());
+    let values: Vec<_> = (1..=15u64).map(|n| config.value(n)).collect();
+    let expected: Vec<_> = (1..=15u64).map(|n| Config::default().value(n)).collect();
+    assert_eq!(values, expected);
+    assert_eq!(values[14], "FizzBuzz");
+}
+
+#[test]
+fn test_invalid_rules() {
+    let zero = indoc! {r#"
+        [[rules]]
+        divisor = 3
+        word = "Fizz"
+
+        [[rules]]
+        divisor = 0
+        word = "Zero"
+    "#};
+    assert_eq!(
+        rule_error(Config::from_toml(zero)),
+        (2, "divisor must be greater than 0".to_string())
+    );
+
+    let duplicate = r#"{"rules": [{"divisor": 3, "word": "Fizz"}, {"divisor": 5, "word": "Buzz"}, {"divisor": 7, "word": "Fizz"}]}"#;
+    assert_eq!(
+        rule_error(Config::from_json(duplicate)),
+        (3, "duplicate word (already used by rule #1)".to_string())
+    );
+
+    let unknown = indoc! {r#"
+        [[rules]]
+        kind = "odd"
+        word = "Odd"
+    "#};
+    let error = Config::from_toml(unknown).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        r#"rule #1 (word = "Odd"): unknown rule kind `odd` (expected divisible, contains or prime)"#
+    );
+
+    for toml in [
+        "[[rules]]\nword = \"Fizz\"",
+        "[[rules]]\ndivisor = 3\nword = \"\"",
+        "[[rules]]\nkind = \"contains\"\ndigit = 10\nword = \"X\"",
+        "[[rules]]\nkind = \"prime\"\ndivisor = 3\nword = \"X\"",
+    ] {
+        rule_error(Config::from_toml(toml));
+    }
+}
+
+#[test]
+fn test_parse_error() {
+    for toml in ["rules = 3", "separator = ", "unknown = 1"] {
+        assert!(
+            matches!(Config::from_toml(toml), Err(ConfigError::Parse(_))),
+            "{}",
+            toml
+        );
+    }
+    assert!(matches!(Config::from_json("{"), Err(ConfigError::Parse(_))));
+}
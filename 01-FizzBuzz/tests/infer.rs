@@ -0,0 +1,153 @@
+use fizz_buzz::{infer, Config, Inference, Issue, Locale, WordRule};
+
+fn rules(specs: &[&str]) -> Vec<WordRule> {
+    specs.iter().map(|s| s.parse().unwrap()).collect()
+}
+
+fn transcript(rules: &[WordRule], range: std::ops::RangeInclusive<u64>) -> String {
+    let config = Config::new(rules.to_vec());
+    range.map(|n| config.value(n) + "\n").collect()
+}
+
+fn inferred(text: &str, start: Option<u64>) -> Inference {
+    infer(text.as_bytes(), start).unwrap()
+}
+
+fn names(inference: &Inference) -> Vec<String> {
+    inference.rules.iter().map(WordRule::to_string).collect()
+}
+
+// 推定したルールで再生成した出力が, 元の出力と一致すれば可
+#[test]
+fn test_infer_regenerates() {
+    for (specs, range) in [
+        (&["3=Fizz", "5=Buzz"][..], 1..=100),
+        (&["3=Fizz", "5=Buzz", "7=Bazz"][..], 1..=210),
+        (&["5=Buzz", "3=Fizz"][..], 1..=30),
+        // 単独では現れない単語も, 連結された単語から分解する
+        (&["2=Even", "4=Four"][..], 1..=20),
+        (&["4=Four", "2=Even"][..], 1..=20),
+        (&["3=Fizz", "5=Buzz"][..], 20..=40),
+    ] {
+        let text = transcript(&rules(specs), range.clone());
+        let inference = inferred(&text, None);
+
+        assert_eq!(names(&inference), specs, "{:?}", specs);
+        assert_eq!(inference.start, *range.start());
+        assert!(inference.issues.is_empty(), "{:?}", inference.issues);
+        assert_eq!(transcript(&inference.rules, range), text);
+    }
+}
+
+// 組み込みの単語の組も, 連結された単語から分解できれば可
+#[test]
+fn test_infer_locale() {
+    let text = transcript(&Locale::Ja.rules(), 1..=15);
+    let inference = inferred(&text, None);
+    assert_eq!(names(&inference), ["3=フィズ", "5=バズ"]);
+}
+
+// 推定したルールが, 設定ファイルとして読み込めれば可
+#[test]
+fn test_infer_to_toml() {
+    let text = transcript(&rules(&["3=Fizz", "5=Buzz \"Quoted\""]), 1..=15);
+    let inference = inferred(&text, None);
+
+    let toml = inference.to_toml();
+    assert!(
+        toml.starts_with("# inferred from 15 lines (1..=15)\n"),
+        "{}",
+        toml
+    );
+    let config = Config::from_toml(&toml).unwrap();
+    assert_eq!(transcript(&config.rules, 1..=15), text);
+}
+
+// 1回しか現れない単語は, 最小の除数を使い曖昧さを示せば可
+#[test]
+fn test_infer_ambiguous_divisor() {
+    let inference = inferred("4\n5\nFizz\n7\n8\n", None);
+    assert_eq!(names(&inference), ["3=Fizz"]);
+    assert!(inference.is_consistent());
+    assert_eq!(
+        inference.issues,
+        [Issue::AmbiguousDivisor {
+            word: "Fizz".to_string(),
+            n: 6,
+            divisors: vec![3, 6],
+        }]
+    );
+
+    // 1行しかない場合は, その数値の約数すべてが候補になる
+    let inference = inferred("Fizz\n", Some(3));
+    assert_eq!(
+        inference.issues[0].to_string(),
+        "\"Fizz\" appears only at 3, so its divisor could be any of 1, 3 (using 1)"
+    );
+}
+
+// 複数の方法で分解できる単語は, 曖昧さを示せば可
+#[test]
+fn test_infer_ambiguous_split() {
+    let inference = inferred("1\na\nab\nabc\nbc\n6\nc\nabc\n", None);
+    let splits: Vec<_> = inference
+        .issues
+        .iter()
+        .filter(|issue| matches!(issue, Issue::AmbiguousSplit { .. }))
+        .map(Issue::to_string)
+        .collect();
+    assert_eq!(
+        splits,
+        ["line 4: \"abc\" can be split in more than one way (a+bc or ab+c)"]
+    );
+}
+
+// 矛盾する行は, 行番号とともに示せば可
+#[test]
+fn test_infer_contradictions() {
+    let mut lines: Vec<_> = transcript(&WordRule::classic(), 1..=30)
+        .lines()
+        .map(str::to_string)
+        .collect();
+    lines[8] = "9".to_string();
+    lines[10] = "12".to_string();
+    lines[29] = "BuzzFizz".to_string();
+    lines[6] = String::new();
+    let inference = inferred(&(lines.join("\n") + "\n"), None);
+
+    assert!(!inference.is_consistent());
+    let issues: Vec<_> = inference.issues.iter().map(Issue::to_string).collect();
+    assert_eq!(
+        issues,
+        [
+            "line 7: empty line",
+            "line 11: expected 11, found \"12\"",
+            "line 9: expected \"Fizz\" (multiple of 3), found \"9\"",
+            "line 30: \"Buzz\" comes before \"Fizz\", contradicting the order on other lines",
+        ]
+    );
+    assert!(inference.issues.iter().all(Issue::is_contradiction));
+}
+
+// どの除数でも説明できない単語は, 現れた数値とともに示せば可
+#[test]
+fn test_infer_no_divisor() {
+    let inference = inferred("1\nOdd\n3\nOdd\nOdd\n", None);
+    assert!(inference.rules.is_empty());
+    assert_eq!(
+        inference.issues[0].to_string(),
+        "\"Odd\" appears at 2, 4, 5, which are not the multiples of any divisor"
+    );
+}
+
+// 分解から得た単語がどの行にも使われない場合は, パニックせずにエラーを返せば可
+#[test]
+fn test_infer_unused_atom() {
+    let e = infer("ABA\nAABCBA\nBA\nBA\n".as_bytes(), None).unwrap_err();
+    assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+    assert!(
+        e.to_string().starts_with("cannot infer the rules: "),
+        "{}",
+        e
+    );
+}
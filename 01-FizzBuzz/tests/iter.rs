@@ -0,0 +1,104 @@
+use fizz_buzz::{FizzBuzz, FizzBuzzValue};
+
+// 1から15までの値が順に返れば可
+#[test]
+fn test_iter_15() {
+    use FizzBuzzValue::{Buzz, Fizz, Number};
+
+    let values: Vec<_> = FizzBuzz::new(15).collect();
+    assert_eq!(
+        values,
+        vec![
+            Number(1),
+            Number(2),
+            Fizz,
+            Number(4),
+            Buzz,
+            Fizz,
+            Number(7),
+            Number(8),
+            Fizz,
+            Buzz,
+            Number(11),
+            Fizz,
+            Number(13),
+            Number(14),
+            FizzBuzzValue::FizzBuzz,
+        ]
+    );
+}
+
+// Displayで出力用の文字列になれば可
+#[test]
+fn test_display() {
+    let lines: Vec<_> = FizzBuzz::new(5).map(|v| v.to_string()).collect();
+    assert_eq!(lines, ["1", "2", "Fizz", "4", "Buzz"]);
+}
+
+// 逆順に取り出しても同じ値になれば可
+#[test]
+fn test_double_ended() {
+    let forward: Vec<_> = FizzBuzz::new(100).collect();
+    let mut backward: Vec<_> = FizzBuzz::new(100).rev().collect();
+    backward.reverse();
+    assert_eq!(forward, backward);
+
+    let mut iter = FizzBuzz::new(6);
+    assert_eq!(iter.next(), Some(FizzBuzzValue::Number(1)));
+    assert_eq!(iter.next_back(), Some(FizzBuzzValue::Fizz));
+    assert_eq!(iter.len(), 4);
+}
+
+// 残り個数が常に正しく返れば可
+#[test]
+fn test_exact_size() {
+    let mut iter = FizzBuzz::new(10);
+    assert_eq!(iter.len(), 10);
+    iter.nth(3);
+    assert_eq!(iter.len(), 6);
+    iter.nth_back(1);
+    assert_eq!(iter.len(), 4);
+    assert_eq!(FizzBuzz::new(0).len(), 0);
+}
+
+// 巨大な範囲でもnthが即座に返れば可
+#[test]
+fn test_nth() {
+    let mut iter = FizzBuzz::new(u64::MAX);
+    assert_eq!(iter.nth(14), Some(FizzBuzzValue::FizzBuzz));
+    assert_eq!(
+        iter.nth(1_000_000_000_000),
+        Some(FizzBuzzValue::Number(1_000_000_000_016))
+    );
+    assert_eq!(
+        FizzBuzz::new(u64::MAX).last(),
+        Some(FizzBuzzValue::FizzBuzz)
+    );
+
+    let mut iter = FizzBuzz::new(3);
+    assert_eq!(iter.nth(3), None);
+    assert_eq!(iter.next(), None);
+}
+
+// 任意の範囲を指定できれば可
+#[test]
+fn test_range() {
+    let lines: Vec<_> = FizzBuzz::range(9..=11).map(|v| v.to_string()).collect();
+    assert_eq!(lines, ["Fizz", "Buzz", "11"]);
+    let (start, end) = (5, 4);
+    assert_eq!(FizzBuzz::range(start..=end).len(), 0);
+
+    // 個数がu64に収まらない0..=u64::MAXも, 末尾のu64::MAXまで返す
+    let mut iter = FizzBuzz::range(0..=u64::MAX);
+    assert_eq!(iter.size_hint(), (usize::MAX, None));
+    assert_eq!(
+        iter.nth(u64::MAX as usize - 1),
+        Some(FizzBuzzValue::Number(u64::MAX - 1))
+    );
+    assert_eq!(iter.next(), Some(FizzBuzzValue::FizzBuzz));
+    assert_eq!(iter.next(), None);
+    assert_eq!(
+        FizzBuzz::range(0..=u64::MAX).next_back(),
+        Some(FizzBuzzValue::FizzBuzz)
+    );
+}
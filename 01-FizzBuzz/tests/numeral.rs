@@ -0,0 +1,67 @@
+use fizz_buzz::{Config, Notation, Numerals};
+
+#[test]
+fn test_radix() {
+    assert_eq!(Notation::Radix(2).format(10u64), "1010");
+    assert_eq!(Notation::Radix(16).format(255u64), "ff");
+    assert_eq!(Notation::Radix(36).format(35u64), "z");
+    assert_eq!(Notation::Radix(16).format(-255i64), "-ff");
+    assert_eq!(Notation::Radix(2).format(0u64), "0");
+    assert_eq!(Notation::Radix(16).format(u64::MAX), "ffffffffffffffff");
+}
+
+#[test]
+fn test_roman() {
+    let roman = |n: u64| Notation::Roman.format(n);
+    assert_eq!(roman(1), "I");
+    assert_eq!(roman(4), "IV");
+    assert_eq!(roman(14), "XIV");
+    assert_eq!(roman(1994), "MCMXCIV");
+    assert_eq!(roman(3999), "MMMCMXCIX");
+
+    // 表せない数値は10進数
+    assert_eq!(roman(0), "0");
+    assert_eq!(roman(4000), "4000");
+    assert_eq!(Notation::Roman.format(-4i64), "-4");
+}
+
+#[test]
+fn test_kanji() {
+    let kanji = |n: u64| Notation::Kanji.format(n);
+    assert_eq!(kanji(0), "〇");
+    assert_eq!(kanji(7), "七");
+    assert_eq!(kanji(10), "十");
+    assert_eq!(kanji(15), "十五");
+    assert_eq!(kanji(111), "百十一");
+    assert_eq!(kanji(2024), "二千二十四");
+    assert_eq!(kanji(10_000), "一万");
+    assert_eq!(kanji(12_000), "一万二千");
+    assert_eq!(kanji(100_000_001), "一億一");
+    assert_eq!(Notation::Kanji.format(-1i64), "-1");
+}
+
+#[test]
+fn test_numerals() {
+    assert_eq!("roman".parse(), Ok(Numerals::Roman));
+    assert_eq!("KANJI".parse(), Ok(Numerals::Kanji));
+    assert_eq!(
+        "greek".parse::<Numerals>(),
+        Err("unknown numerals `greek` (expected one of: roman, kanji)".to_string())
+    );
+    assert_eq!(Notation::from(Numerals::Kanji), Notation::Kanji);
+}
+
+#[test]
+fn test_config_notation() {
+    let mut config = Config::default();
+    assert!(config.is_plain());
+
+    config.notation = Notation::Roman;
+    assert!(!config.is_plain());
+    let values: Vec<_> = (1..=6u64).map(|n| config.value(n)).collect();
+    assert_eq!(values, ["I", "II", "Fizz", "IV", "Buzz", "Fizz"]);
+
+    // 置き換えの`{n}`も同じ表記になる
+    config.fallback = Some("<{n}>".to_string());
+    assert_eq!(config.value(7u64), "<VII>");
+}
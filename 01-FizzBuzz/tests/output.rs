@@ -0,0 +1,166 @@
+mod common;
+
+use std::{
+    fs,
+    io::{Read, Write},
+    path::Path,
+};
+
+use common::TempDir;
+use fizz_buzz::{scan, Compression, Output, MEMBER_LEN};
+
+fn write(path: &Path, content: &str) {
+    let mut output = Output::create(path).unwrap();
+    output.write_all(content.as_bytes()).unwrap();
+    output.finish().unwrap();
+}
+
+fn read(path: &Path) -> String {
+    let file = fs::File::open(path).unwrap();
+    let mut input: Box<dyn Read> = match Compression::from_path(path) {
+        Compression::None => Box::new(file),
+        Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(file)),
+        Compression::Zstd => Box::new(zstd::Decoder::new(file).unwrap()),
+    };
+    let mut s = String::new();
+    input.read_to_string(&mut s).unwrap();
+    s
+}
+
+#[test]
+fn test_compression_from_path() {
+    let compression = |path: &str| Compression::from_path(Path::new(path));
+    assert_eq!(compression("out.txt"), Compression::None);
+    assert_eq!(compression("out"), Compression::None);
+    assert_eq!(compression("out.txt.gz"), Compression::Gzip);
+    assert_eq!(compression("OUT.GZ"), Compression::Gzip);
+    assert_eq!(compression("out.zst"), Compression::Zstd);
+    assert_eq!(compression("out.txt.zstd"), Compression::Zstd);
+
+    assert_eq!(Compression::Zstd.to_string(), "zstd");
+    assert_eq!("GZIP".parse(), Ok(Compression::Gzip));
+    assert_eq!(
+        "xz".parse::<Compression>(),
+        Err("unknown compression `xz` (expected one of: none, gzip, zstd)".to_string())
+    );
+}
+
+// 圧縮の有無にかかわらず, 書き出した内容を読み戻せれば可
+#[test]
+fn test_create() {
+    let dir = TempDir::new("create");
+    for file in ["out.txt", "out.txt.gz", "out.txt.zst"] {
+        let path = dir.join(file);
+        write(&path, "1\n2\nFizz\n");
+        assert_eq!(read(&path), "1\n2\nFizz\n", "{}", file);
+
+        let resume = scan(&path).unwrap().unwrap();
+        assert_eq!(resume.lines, 3);
+        assert_eq!(resume.last_line.as_deref(), Some("Fizz"));
+        assert!(resume.is_clean());
+    }
+    assert_eq!(scan(&dir.join("missing.txt")).unwrap(), None);
+}
+
+// 途中までの行を除いて続きを書き出せれば可
+#[test]
+fn test_append() {
+    let dir = TempDir::new("append");
+    for file in ["out.txt", "out.txt.gz", "out.txt.zst"] {
+        let path = dir.join(file);
+        write(&path, "1\n2\nFi");
+
+        let resume = scan(&path).unwrap().unwrap();
+        assert_eq!(resume.lines, 2);
+        assert_eq!(resume.last_line.as_deref(), Some("2"));
+        assert!(!resume.is_clean());
+
+        let mut output = Output::append(&path, &resume).unwrap();
+        output.write_all(b"Fizz\n4\n").unwrap();
+        output.finish().unwrap();
+        assert_eq!(read(&path), "1\n2\nFizz\n4\n", "{}", file);
+    }
+}
+
+// 書き出しの途中で切れた圧縮ファイルも, 読めたところまでの完全な行を扱えれば可
+#[test]
+fn test_scan_truncated() {
+    let dir = TempDir::new("truncated");
+    let content: String = (1..=10_000).map(|n| format!("{}\n", n)).collect();
+    for file in ["out.txt.gz", "out.txt.zst"] {
+        let path = dir.join(file);
+        write(&path, &content);
+        let len = fs::metadata(&path).unwrap().len();
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(len / 2)
+            .unwrap();
+
+        let resume = scan(&path).unwrap().unwrap();
+        assert!(!resume.is_clean(), "{}", file);
+        assert!(resume.lines < 10_000, "{}", file);
+        if let Some(last_line) = &resume.last_line {
+            assert_eq!(*last_line, resume.lines.to_string(), "{}", file);
+        }
+    }
+}
+
+// 複数のメンバに分かれた圧縮ファイルは, 完全なメンバを書き直さずに続きを追記できれば可
+#[test]
+fn test_append_members() {
+    let dir = TempDir::new("members");
+    let content: String = (1..)
+        .map(|n| format!("{}\n", n))
+        .take_while({
+            let mut len = 0;
+            move |line| {
+                len += line.len() as u64;
+                len <= MEMBER_LEN * 5 / 2
+            }
+        })
+        .collect();
+    for file in ["out.txt.gz", "out.txt.zst"] {
+        let path = dir.join(file);
+        write(&path, &content);
+        // 先頭に, 展開すると空になる目印のメンバ (フレーム) を置く
+        let mut written = match Compression::from_path(&path) {
+            Compression::Gzip => flate2::GzBuilder::new()
+                .filename("marker")
+                .write(Vec::new(), flate2::Compression::default())
+                .finish()
+                .unwrap(),
+            _ => [&[0x50, 0x2a, 0x4d, 0x18, 6, 0, 0, 0][..], b"marker"].concat(),
+        };
+        written.extend(fs::read(&path).unwrap());
+        fs::write(&path, &written).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(written.len() as u64 - 100)
+            .unwrap();
+
+        let resume = scan(&path).unwrap().unwrap();
+        assert!(!resume.is_clean(), "{}", file);
+        let (done, rest) = content.split_at(
+            content
+                .match_indices('\n')
+                .nth(resume.lines as usize - 1)
+                .unwrap()
+                .0
+                + 1,
+        );
+        let mut output = Output::append(&path, &resume).unwrap();
+        output.write_all(rest.as_bytes()).unwrap();
+        output.finish().unwrap();
+        assert_eq!(read(&path), content, "{}", file);
+        assert!(done.len() as u64 > MEMBER_LEN * 2, "{}", file);
+
+        // 目印と最初の2つのメンバは書き直さずに残る
+        let appended = fs::read(&path).unwrap();
+        let kept = written.len() / 2;
+        assert_eq!(appended[..kept], written[..kept], "{}", file);
+    }
+}
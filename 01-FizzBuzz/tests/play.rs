@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use fizz_buzz::{play, Config, GameEnd, PlayOptions};
+
+fn run(input: &'static str, options: PlayOptions) -> (String, fizz_buzz::Summary) {
+    let mut out = Vec::new();
+    let summary = play(input.as_bytes(), &mut out, &Config::default(), options).unwrap();
+    (String::from_utf8(out).unwrap(), summary)
+}
+
+fn up_to(n: u64) -> PlayOptions {
+    PlayOptions {
+        n,
+        ..PlayOptions::default()
+    }
+}
+
+#[test]
+fn test_play_completed() {
+    let (out, summary) = run("2\n4\n fizz \n8\nBuzz\n", up_to(10));
+    assert_eq!(summary.end, GameEnd::Completed);
+    assert_eq!(
+        (summary.correct, summary.turns, summary.best_streak),
+        (5, 5, 5)
+    );
+    assert_eq!(summary.reaction_times.len(), 5);
+
+    let mine: Vec<_> = out
+        .lines()
+        .filter_map(|line| line.strip_prefix("me: "))
+        .collect();
+    assert_eq!(mine, ["1", "Fizz", "Buzz", "7", "Fizz"]);
+    assert!(summary.to_string().contains("score: 5/5 (100.0%)\n"));
+}
+
+#[test]
+fn test_play_streak_and_lives() {
+    let (out, summary) = run("2\n4\n6\n8\nBuzz\nFizz\n14\n", up_to(20));
+    assert_eq!(summary.end, GameEnd::Quit);
+    assert_eq!(
+        (summary.correct, summary.turns, summary.best_streak),
+        (6, 7, 4)
+    );
+    assert!(out.contains(r#"wrong: 6 is "Fizz", not "6" (lives: 2)"#));
+
+    let (_, summary) = run(
+        "Fizz\n4\nx\n",
+        PlayOptions {
+            lives: 2,
+            ..up_to(20)
+        },
+    );
+    assert_eq!(summary.end, GameEnd::GameOver);
+    assert_eq!(
+        (summary.correct, summary.turns, summary.best_streak),
+        (1, 3, 1)
+    );
+}
+
+#[test]
+fn test_play_quit() {
+    let (_, summary) = run("2\nq\n4\n", up_to(100));
+    assert_eq!(summary.end, GameEnd::Quit);
+    assert_eq!(summary.turns, 1);
+
+    let (_, summary) = run("", up_to(100));
+    assert_eq!(summary.end, GameEnd::Quit);
+    assert_eq!(summary.turns, 0);
+    assert!(summary.to_string().contains("reaction time: -\n"));
+}
+
+#[test]
+fn test_play_timeout() {
+    // 入力が終わらないまま, 回答がない
+    let (reader, writer) = std::io::pipe().unwrap();
+    let options = PlayOptions {
+        n: 100,
+        lives: 2,
+        timeout: Some(Duration::from_millis(50)),
+    };
+
+    let mut out = Vec::new();
+    let summary = play(reader, &mut out, &Config::default(), options).unwrap();
+    drop(writer);
+
+    assert_eq!(summary.end, GameEnd::GameOver);
+    assert_eq!((summary.correct, summary.turns), (0, 2));
+    assert!(summary.reaction_times.is_empty());
+    assert!(String::from_utf8(out)
+        .unwrap()
+        .contains(r#"time up: 4 is "4" (lives: 0)"#));
+}
+
+#[test]
+fn test_play_late_answer() {
+    // 2への回答が時間切れの後に届く. 次の手番 (4) の回答とはしない
+    let (reader, mut writer) = std::io::pipe().unwrap();
+    let typist = std::thread::spawn(move || {
+        use std::io::Write;
+        for (delay, line) in [(500, "2\n"), (150, "4\n"), (100, "Fizz\n")] {
+            std::thread::sleep(Duration::from_millis(delay));
+            writer.write_all(line.as_bytes()).unwrap();
+        }
+    });
+    let options = PlayOptions {
+        n: 6,
+        lives: 3,
+        timeout: Some(Duration::from_millis(400)),
+    };
+
+    let mut out = Vec::new();
+    let summary = play(reader, &mut out, &Config::default(), options).unwrap();
+    typist.join().unwrap();
+
+    assert_eq!(summary.end, GameEnd::Completed);
+    assert_eq!((summary.correct, summary.turns), (2, 3));
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains(r#"time up: 2 is "2" (lives: 2)"#));
+    assert!(!out.contains("wrong"));
+}
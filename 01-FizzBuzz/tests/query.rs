@@ -0,0 +1,85 @@
+use std::num::NonZeroU64;
+
+use fizz_buzz::{can_appear, classify, find_nth, value_at, DivisibleBy, FindError, WordRule};
+
+fn nth(n: u64) -> NonZeroU64 {
+    NonZeroU64::new(n).unwrap()
+}
+
+// 1つの数値に対する値が求まれば可
+#[test]
+fn test_value_at() {
+    let rules = WordRule::classic();
+    assert_eq!(value_at(15u64, &rules), "FizzBuzz");
+    assert_eq!(value_at(123_456_789u64, &rules), "Fizz");
+    assert_eq!(value_at(-7i64, &rules), "-7");
+}
+
+// 逆引きの結果が, 先頭から数えた結果と一致すれば可
+#[test]
+fn test_find_nth_agrees_with_iteration() {
+    let rule_sets = [
+        WordRule::classic(),
+        vec![
+            WordRule::new(DivisibleBy(3), "Fizz"),
+            WordRule::new(DivisibleBy(5), "Buzz"),
+            WordRule::new(DivisibleBy(7), "Bazz"),
+        ],
+        vec![
+            WordRule::new(DivisibleBy(2), "Foo"),
+            WordRule::new(DivisibleBy(4), "Bar"),
+            WordRule::new(DivisibleBy(6), "Foo"),
+        ],
+    ];
+    for rules in &rule_sets {
+        let mut seen: Vec<(String, u64)> = Vec::new();
+        for n in 1..=500u64 {
+            let Some(word) = classify(n, rules) else {
+                continue;
+            };
+            let k = match seen.iter_mut().find(|(w, _)| *w == word) {
+                Some((_, k)) => {
+                    *k += 1;
+                    *k
+                }
+                None => {
+                    seen.push((word.clone(), 1));
+                    1
+                }
+            };
+            assert_eq!(find_nth(&word, nth(k), rules), Ok(n), "{} #{}", word, k);
+        }
+    }
+}
+
+// 巨大な順位でも即座に求まれば可
+#[test]
+fn test_find_nth_large() {
+    let rules = WordRule::classic();
+    assert_eq!(find_nth("Buzz", nth(1000), &rules), Ok(7495));
+    assert_eq!(
+        find_nth("FizzBuzz", nth(u64::MAX / 15), &rules),
+        Ok(u64::MAX / 15 * 15)
+    );
+    assert_eq!(
+        find_nth("FizzBuzz", nth(u64::MAX / 15 + 1), &rules),
+        Err(FindError::OutOfRange)
+    );
+}
+
+// 決して現れない単語はエラーとなれば可
+#[test]
+fn test_find_never_appears() {
+    let rules = vec![
+        WordRule::new(DivisibleBy(2), "Foo"),
+        WordRule::new(DivisibleBy(4), "Bar"),
+    ];
+    assert!(can_appear("Foo", &rules).unwrap());
+    assert!(can_appear("FooBar", &rules).unwrap());
+    assert!(!can_appear("Bar", &rules).unwrap());
+    assert!(!can_appear("Baz", &rules).unwrap());
+    assert_eq!(
+        find_nth("Bar", nth(1), &rules),
+        Err(FindError::NeverAppears("Bar".to_string()))
+    );
+}
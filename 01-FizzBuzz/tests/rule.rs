@@ -0,0 +1,124 @@
+use fizz_buzz::{
+    classify, write_with_rules, ContainsDigit, ContainsDigitIn, Custom, DivisibleBy, IsPrime, Rule,
+    WordRule,
+};
+
+#[test]
+fn test_divisible_by() {
+    assert!(DivisibleBy(3).matches(0));
+    assert!(DivisibleBy(3).matches(9));
+    assert!(!DivisibleBy(3).matches(10));
+    assert_eq!(DivisibleBy(3).divisor(), Some(3));
+}
+
+#[test]
+fn test_contains_digit() {
+    let rule = ContainsDigit(3);
+    let matched: Vec<u64> = (1..=40).filter(|&n| rule.matches(n)).collect();
+    assert_eq!(matched, [3, 13, 23, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39]);
+    assert!(ContainsDigit(0).matches(0));
+    assert!(!ContainsDigit(1).matches(0));
+    assert_eq!(rule.divisor(), None);
+    assert_eq!(
+        rule.matches_decimal("1000000000000000000000003"),
+        Some(true)
+    );
+}
+
+#[test]
+fn test_contains_digit_in() {
+    let rule = ContainsDigitIn {
+        digit: 15,
+        radix: 16,
+    };
+    let matched: Vec<u64> = (1..=40).filter(|&n| rule.matches(n)).collect();
+    assert_eq!(matched, [15, 31]);
+    assert!(ContainsDigitIn { digit: 0, radix: 2 }.matches(0));
+    assert!(!ContainsDigitIn { digit: 0, radix: 2 }.matches(7));
+    assert_eq!(rule.to_string(), "contains:f");
+}
+
+#[test]
+fn test_is_prime() {
+    // エラトステネスの篩と比較する
+    const N: usize = 10_000;
+    let mut sieve = vec![true; N];
+    sieve[0] = false;
+    sieve[1] = false;
+    for i in 2..N {
+        if sieve[i] {
+            for j in (i * i..N).step_by(i) {
+                sieve[j] = false;
+            }
+        }
+    }
+    for (n, &prime) in sieve.iter().enumerate() {
+        assert_eq!(IsPrime.matches(n as u64), prime, "{}", n);
+    }
+
+    // u64の最大の素数と, 強擬素数
+    assert!(IsPrime.matches(18_446_744_073_709_551_557));
+    assert!(!IsPrime.matches(3_215_031_751));
+    assert!(!IsPrime.matches(u64::MAX));
+}
+
+#[test]
+fn test_custom() {
+    let rules = vec![
+        WordRule::new(Custom::new(|n| n.is_power_of_two()), "Pow"),
+        WordRule::new(DivisibleBy(3), "Fizz"),
+    ];
+    assert_eq!(classify(3u64, &rules).as_deref(), Some("Fizz"));
+    assert_eq!(classify(4u64, &rules).as_deref(), Some("Pow"));
+    assert_eq!(classify(5u64, &rules), None);
+    assert_eq!(classify(-4i64, &rules).as_deref(), Some("Pow"));
+
+    let mut out = Vec::new();
+    write_with_rules(&mut out, 1..=6, &rules).unwrap();
+    assert_eq!(out, b"Pow\nPow\nFizz\nPow\n5\nFizz\n");
+}
+
+#[test]
+fn test_parse() {
+    let parse = |s: &str| s.parse::<WordRule>().map(|rule| rule.to_string());
+    assert_eq!(parse("3=Fizz"), Ok("3=Fizz".to_string()));
+    assert_eq!(parse("div:5=Buzz"), Ok("5=Buzz".to_string()));
+    assert_eq!(parse("contains:7=Bazz"), Ok("contains:7=Bazz".to_string()));
+    assert_eq!(parse("prime=Prime"), Ok("prime=Prime".to_string()));
+
+    for s in ["3", "3=", "0=Zero", "contains:10=X", "contains:=X", "odd=X"] {
+        assert!(parse(s).is_err(), "{}", s);
+    }
+    assert!(WordRule::is_classic(&[
+        "3=Fizz".parse().unwrap(),
+        "5=Buzz".parse().unwrap()
+    ]));
+    assert!(!WordRule::is_classic(&[
+        "contains:3=Fizz".parse().unwrap(),
+        "5=Buzz".parse().unwrap()
+    ]));
+}
+
+#[test]
+fn test_parse_radix() {
+    let parse = |s: &str, radix| WordRule::from_str_radix(s, radix).map(|rule| rule.to_string());
+    assert_eq!(parse("a=Ten", 16), Ok("10=Ten".to_string()));
+    assert_eq!(parse("div:11=Three", 2), Ok("3=Three".to_string()));
+    assert_eq!(
+        parse("contains:f=Eff", 16),
+        Ok("contains:f=Eff".to_string())
+    );
+    assert_eq!(
+        parse("contains:7=Bazz", 10),
+        Ok("contains:7=Bazz".to_string())
+    );
+    assert_eq!(
+        parse("contains:2=Two", 2),
+        Err("invalid digit `2`: expected 0-1".to_string())
+    );
+    assert!(parse("g=G", 16).is_err());
+
+    let rule = WordRule::from_str_radix("contains:1=One", 2).unwrap();
+    let matched: Vec<u64> = (0..=4).filter(|&n| rule.matches(n)).collect();
+    assert_eq!(matched, [1, 2, 3, 4]);
+}
@@ -0,0 +1,271 @@
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    num::NonZeroUsize,
+    thread,
+    time::Duration,
+};
+
+use fizz_buzz::{serve, Config, FizzBuzz, ServeOptions, WordRule};
+
+// 空いているポートでサーバを起動する
+fn start(config: Config, max_range: u64) -> SocketAddr {
+    let options = ServeOptions {
+        max_range,
+        ..ServeOptions::default()
+    };
+    start_with(config, options)
+}
+
+fn start_with(config: Config, options: ServeOptions) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || serve(listener, config, options));
+    addr
+}
+
+/// 応答の状態コード, ヘッダ, 本文 (チャンク形式は復号済み)
+struct Response {
+    status: u16,
+    head: String,
+    body: String,
+}
+
+fn request(addr: SocketAddr, raw: &str) -> Response {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.write_all(raw.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    let body = if head.contains("Transfer-Encoding: chunked") {
+        dechunk(body)
+    } else {
+        body.to_string()
+    };
+
+    Response {
+        status,
+        head: head.to_string(),
+        body,
+    }
+}
+
+fn get(addr: SocketAddr, target: &str) -> Response {
+    request(
+        addr,
+        &format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target),
+    )
+}
+
+fn dechunk(mut body: &str) -> String {
+    let mut decoded = String::new();
+    loop {
+        let (len, rest) = body.split_once("\r\n").unwrap();
+        let len = usize::from_str_radix(len, 16).unwrap();
+        if len == 0 {
+            assert_eq!(rest, "\r\n");
+            return decoded;
+        }
+        decoded += &rest[..len];
+        body = rest[len..].strip_prefix("\r\n").unwrap();
+    }
+}
+
+// 範囲の値がチャンク形式で返れば可
+#[test]
+fn test_serve_range() {
+    let addr = start(Config::default(), 1_000_000);
+
+    let response = get(addr, "/fizzbuzz?from=1&to=100000");
+    assert_eq!(response.status, 200);
+    assert!(response.head.contains("Transfer-Encoding: chunked"));
+    assert!(response.head.contains("Content-Type: text/plain"));
+    let expected: String = FizzBuzz::new(100_000).map(|v| format!("{}\n", v)).collect();
+    assert_eq!(response.body, expected);
+
+    let response = get(addr, "/fizzbuzz?from=14&to=16&format=json");
+    assert_eq!(response.status, 200);
+    assert!(response.head.contains("Content-Type: application/json"));
+    assert_eq!(
+        response.body,
+        "[\n{\"n\":14,\"value\":\"14\"},\n{\"n\":15,\"value\":\"FizzBuzz\"},\n{\"n\":16,\"value\":\"16\"}\n]\n"
+    );
+
+    // `from`の既定値は1, 降順や負の数も扱う
+    assert_eq!(get(addr, "/fizzbuzz?to=3").body, "1\n2\nFizz\n");
+    assert_eq!(
+        get(addr, "/fizzbuzz?from=1&to=-1&format=csv").body,
+        "n,value\n1,1\n0,FizzBuzz\n-1,-1\n"
+    );
+}
+
+// 1つの値が返れば可
+#[test]
+fn test_serve_value() {
+    let addr = start(Config::default(), 10);
+
+    let response = get(addr, "/fizzbuzz/15");
+    assert_eq!(response.status, 200);
+    assert!(response.head.contains("Content-Length: 9"));
+    assert_eq!(response.body, "FizzBuzz\n");
+
+    assert_eq!(
+        get(addr, "/fizzbuzz/9?format=json").body,
+        "{\"n\":9,\"value\":\"Fizz\"}\n"
+    );
+    assert_eq!(get(addr, "/fizzbuzz/-5").body, "Buzz\n");
+    assert_eq!(
+        get(addr, "/fizzbuzz/100000000000000000000000000000").body,
+        "Buzz\n"
+    );
+
+    // `--stdin`と同じく先頭の`+`を許し, 数値は整えて返す
+    assert_eq!(
+        get(addr, "/fizzbuzz/+015?format=json").body,
+        "{\"n\":15,\"value\":\"FizzBuzz\"}\n"
+    );
+    assert_eq!(
+        get(
+            addr,
+            "/fizzbuzz/%2B100000000000000000000000000000?format=csv"
+        )
+        .body,
+        "n,value\n100000000000000000000000000000,Buzz\n"
+    );
+}
+
+// クエリ文字列の`%XX`を展開すれば可
+#[test]
+fn test_serve_percent_encoding() {
+    let addr = start(Config::default(), 100);
+
+    assert_eq!(
+        get(addr, "/fizzbuzz?from=%2B14&t%6F=1%36").body,
+        "14\nFizzBuzz\n16\n"
+    );
+    assert_eq!(
+        get(addr, "/fizzbuzz?to=3&format=%63sv").body,
+        "n,value\n1,1\n2,2\n3,Fizz\n"
+    );
+
+    // `+`は空白
+    let response = get(addr, "/fizzbuzz?to=+3");
+    assert_eq!(response.status, 400);
+    assert!(response
+        .body
+        .starts_with("error: invalid `to`: \" 3\" is not an integer"));
+
+    for target in ["/fizzbuzz?to=%3", "/fizzbuzz?to=%zz", "/fizzbuzz?to=%FF"] {
+        let response = get(addr, target);
+        assert_eq!(response.status, 400, "{}", target);
+        assert!(
+            response
+                .body
+                .starts_with("error: invalid percent-encoding in `"),
+            "{}: {}",
+            target,
+            response.body
+        );
+    }
+}
+
+// 設定のルールで判定すれば可
+#[test]
+fn test_serve_config() {
+    let mut config = Config::new(vec!["2=Even".parse::<WordRule>().unwrap()]);
+    config.fallback = Some("-".to_string());
+    let addr = start(config, 10);
+
+    assert_eq!(get(addr, "/fizzbuzz?to=4").body, "-\nEven\n-\nEven\n");
+    assert_eq!(get(addr, "/fizzbuzz/7").body, "-\n");
+}
+
+// 不正な要求には400, 404, 405とエラーの内容が返れば可
+#[test]
+fn test_serve_errors() {
+    let addr = start(Config::default(), 100);
+
+    for (target, message) in [
+        (
+            "/fizzbuzz?to=101",
+            "range is too large: 101 values (must be at most 100)",
+        ),
+        (
+            "/fizzbuzz?from=-50&to=50",
+            "range is too large: 101 values (must be at most 100)",
+        ),
+        ("/fizzbuzz", "missing parameter `to`"),
+        (
+            "/fizzbuzz?to=abc",
+            "invalid `to`: \"abc\" is not an integer",
+        ),
+        (
+            "/fizzbuzz?to=10&step=2",
+            "unknown parameter `step` (expected from, to, format)",
+        ),
+        ("/fizzbuzz?to=10&to=20", "duplicate parameter `to`"),
+        ("/fizzbuzz?to=10&format=xml", "unknown format `xml`"),
+        ("/fizzbuzz/abc", "\"abc\" is not an integer"),
+        (
+            "/fizzbuzz/15?to=20",
+            "unknown parameter `to` (expected format)",
+        ),
+    ] {
+        let response = get(addr, target);
+        assert_eq!(response.status, 400, "{}", target);
+        assert!(
+            response.body.starts_with(&format!("error: {}", message)),
+            "{}: {}",
+            target,
+            response.body
+        );
+    }
+
+    let response = get(addr, "/fizz");
+    assert_eq!(response.status, 404);
+    let response = get(addr, "/fizzbuzz/1/2");
+    assert_eq!(response.status, 404);
+
+    let response = request(addr, "POST /fizzbuzz/15 HTTP/1.1\r\n\r\n");
+    assert_eq!(response.status, 405);
+    assert!(response.head.contains("Allow: GET"));
+
+    let response = request(addr, "hello\r\n\r\n");
+    assert_eq!(response.status, 400);
+    assert_eq!(response.body, "error: malformed request line\n");
+}
+
+// 同時に応答する接続の数を越えた接続は, 応答中の接続が終わってから応答されれば可
+#[test]
+fn test_serve_max_connections() {
+    let options = ServeOptions {
+        max_connections: NonZeroUsize::new(1).unwrap(),
+        ..ServeOptions::default()
+    };
+    let addr = start_with(Config::default(), options);
+
+    // 要求を送らない接続が, 唯一のワーカーを占有する
+    let mut idle = TcpStream::connect(addr).unwrap();
+    thread::sleep(Duration::from_millis(100));
+
+    let mut waiting = TcpStream::connect(addr).unwrap();
+    waiting
+        .write_all(b"GET /fizzbuzz/15 HTTP/1.1\r\n\r\n")
+        .unwrap();
+    waiting
+        .set_read_timeout(Some(Duration::from_millis(300)))
+        .unwrap();
+    assert!(waiting.read(&mut [0; 1]).is_err());
+
+    idle.write_all(b"GET /fizzbuzz/3 HTTP/1.1\r\n\r\n").unwrap();
+    let mut response = String::new();
+    idle.read_to_string(&mut response).unwrap();
+    assert!(response.ends_with("\r\n\r\nFizz\n"), "{}", response);
+
+    waiting.set_read_timeout(None).unwrap();
+    let mut response = String::new();
+    waiting.read_to_string(&mut response).unwrap();
+    assert!(response.ends_with("\r\n\r\nFizzBuzz\n"), "{}", response);
+}
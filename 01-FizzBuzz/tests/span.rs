@@ -0,0 +1,66 @@
+use std::num::NonZeroU64;
+
+use fizz_buzz::{classify, Span, WordRule};
+
+fn span(from: i64, to: i64, step: u64) -> Vec<i64> {
+    Span::new(from, to, NonZeroU64::new(step).unwrap())
+        .into_iter()
+        .collect()
+}
+
+// 昇順, 降順ともに増分ごとの値が返れば可
+#[test]
+fn test_span() {
+    assert_eq!(span(1, 10, 3), [1, 4, 7, 10]);
+    assert_eq!(span(-2, 2, 1), [-2, -1, 0, 1, 2]);
+    assert_eq!(span(10, 1, 4), [10, 6, 2]);
+    assert_eq!(span(5, 5, 100), [5]);
+}
+
+// 逆順に取り出しても, 実際に到達する最後の値から返れば可
+#[test]
+fn test_span_rev() {
+    let values: Vec<_> = Span::new(1, 10, NonZeroU64::new(4).unwrap())
+        .into_iter()
+        .rev()
+        .collect();
+    assert_eq!(values, [9, 5, 1]);
+}
+
+// 個数とi番目の値が, 順に取り出した場合と一致すれば可
+#[test]
+fn test_span_get() {
+    for (from, to, step) in [
+        (1, 10, 3),
+        (10, 1, 4),
+        (-2, 2, 1),
+        (i64::MIN, i64::MAX, u64::MAX),
+    ] {
+        let span = Span::new(from, to, NonZeroU64::new(step).unwrap());
+        let values: Vec<_> = (0..span.count()).map(|i| span.get(i).unwrap()).collect();
+        assert_eq!(values, self::span(from, to, step));
+        assert_eq!(span.get(span.count()), None);
+    }
+}
+
+// i64の端でも桁あふれせずに終了すれば可
+#[test]
+fn test_span_edges() {
+    assert_eq!(
+        span(i64::MAX - 5, i64::MAX, 2),
+        [i64::MAX - 5, i64::MAX - 3, i64::MAX - 1]
+    );
+    assert_eq!(span(i64::MIN + 1, i64::MIN, 1), [i64::MIN + 1, i64::MIN]);
+    assert_eq!(span(i64::MIN, i64::MAX, u64::MAX), [i64::MIN, i64::MAX]);
+}
+
+// 0と負の数が数学的に判定されれば可
+#[test]
+fn test_classify_signed() {
+    let rules = WordRule::classic();
+    assert_eq!(classify(0i64, &rules).as_deref(), Some("FizzBuzz"));
+    assert_eq!(classify(-3i64, &rules).as_deref(), Some("Fizz"));
+    assert_eq!(classify(-10i64, &rules).as_deref(), Some("Buzz"));
+    assert_eq!(classify(-7i64, &rules), None);
+    assert_eq!(classify(i64::MIN, &rules), None);
+}
@@ -0,0 +1,140 @@
+use std::num::NonZeroU64;
+
+use fizz_buzz::{classify, stats, stats_up_to, DivisibleBy, Progression, Span, WordRule};
+
+// 1つずつ判定して数えた結果
+fn brute_force(
+    values: impl Iterator<Item = i64>,
+    rules: &[WordRule],
+) -> (Vec<(String, u128)>, u128) {
+    let mut words: Vec<(String, u128)> = Vec::new();
+    let mut numbers = 0;
+    for n in values {
+        match classify(n, rules) {
+            Some(word) => match words.iter_mut().find(|(w, _)| *w == word) {
+                Some((_, count)) => *count += 1,
+                None => words.push((word, 1)),
+            },
+            None => numbers += 1,
+        }
+    }
+
+    (words, numbers)
+}
+
+fn assert_agrees(span: Span, rules: &[WordRule]) {
+    let stats = stats(Progression::from(span), rules).unwrap();
+    let (words, numbers) = brute_force(span.into_iter(), rules);
+
+    assert_eq!(stats.numbers, numbers, "{}", span);
+    for (word, count) in words {
+        assert_eq!(stats.count(&word), Some(count), "{} {}", span, word);
+    }
+    assert_eq!(stats.total(), span.into_iter().count() as u128);
+}
+
+// 1から100までの各単語の個数が求まれば可
+#[test]
+fn test_stats_100() {
+    let stats = stats_up_to(100, &WordRule::classic()).unwrap();
+    assert_eq!(stats.count("Fizz"), Some(27));
+    assert_eq!(stats.count("Buzz"), Some(14));
+    assert_eq!(stats.count("FizzBuzz"), Some(6));
+    assert_eq!(stats.numbers, 53);
+    assert_eq!(
+        stats.to_string(),
+        "Fizz: 27\nBuzz: 14\nFizzBuzz: 6\nNumber: 53\n"
+    );
+}
+
+// 負の数, 降順, 増分, 任意のルールを含む範囲で, 1つずつ数えた結果と一致すれば可
+#[test]
+fn test_stats_agrees_with_brute_force() {
+    let rule_sets = [
+        WordRule::classic(),
+        vec![
+            WordRule::new(DivisibleBy(3), "Fizz"),
+            WordRule::new(DivisibleBy(5), "Buzz"),
+            WordRule::new(DivisibleBy(7), "Bazz"),
+        ],
+        vec![
+            WordRule::new(DivisibleBy(4), "Foo"),
+            WordRule::new(DivisibleBy(6), "Bar"),
+            WordRule::new(DivisibleBy(4), "Baz"),
+        ],
+        vec![WordRule::new(DivisibleBy(1), "One")],
+    ];
+    for rules in &rule_sets {
+        for (from, to) in [(1, 300), (-150, 150), (97, -40), (0, 0), (-7, -7)] {
+            for step in [1, 2, 6, 35, 1000] {
+                assert_agrees(Span::new(from, to, NonZeroU64::new(step).unwrap()), rules);
+            }
+        }
+    }
+}
+
+// 巨大な範囲や巨大な除数でも, 桁あふれせずに求まれば可
+#[test]
+fn test_stats_large() {
+    let all = stats_up_to(u64::MAX, &WordRule::classic()).unwrap();
+    assert_eq!(all.total(), u128::from(u64::MAX));
+    assert_eq!(all.count("FizzBuzz"), Some(u128::from(u64::MAX / 15)));
+
+    let rules = vec![
+        WordRule::new(DivisibleBy(u64::MAX), "Max"),
+        WordRule::new(DivisibleBy(u64::MAX - 1), "Almost"),
+    ];
+    let span = Span::new(i64::MIN, i64::MAX, NonZeroU64::MIN);
+    let huge = stats(Progression::from(span), &rules).unwrap();
+    assert_eq!(huge.count("MaxAlmost"), Some(1));
+    assert_eq!(huge.count("Max"), Some(0));
+    assert_eq!(huge.total(), 1 << 64);
+}
+
+// 増分が巨大で2^64を越えて進む数列でも, i128で1つずつ数えた結果と一致すれば可
+#[test]
+fn test_stats_huge_step() {
+    const P: u64 = 18_446_744_073_709_551_557; // 2^64未満の最大の素数
+    let rule_sets = [
+        vec![
+            WordRule::new(DivisibleBy(3), "A"),
+            WordRule::new(DivisibleBy(P), "B"),
+        ],
+        vec![
+            WordRule::new(DivisibleBy(u64::MAX), "Max"),
+            WordRule::new(DivisibleBy(u64::MAX - 1), "Almost"),
+            WordRule::new(DivisibleBy(2), "Even"),
+        ],
+    ];
+    for rules in &rule_sets {
+        for first in [0i128, 1, -3, i128::from(P), -i128::from(u64::MAX)] {
+            for step in [P, u64::MAX, u64::MAX - 1, 1 << 63] {
+                let values = Progression::new(first, NonZeroU64::new(step).unwrap(), 40);
+                let stats = stats(values, rules).unwrap();
+
+                let mut words: Vec<(String, u128)> = Vec::new();
+                let mut numbers = 0;
+                for k in 0..40 {
+                    let n = first + k * i128::from(step);
+                    let word: String = rules
+                        .iter()
+                        .filter(|rule| n.rem_euclid(i128::from(rule.divisor().unwrap())) == 0)
+                        .map(|rule| rule.word.as_str())
+                        .collect();
+                    match words.iter_mut().find(|(w, _)| *w == word) {
+                        _ if word.is_empty() => numbers += 1,
+                        Some((_, count)) => *count += 1,
+                        None => words.push((word, 1)),
+                    }
+                }
+
+                let context = format!("first {} step {}", first, step);
+                assert_eq!(stats.numbers, numbers, "{}", context);
+                for (word, count) in words {
+                    assert_eq!(stats.count(&word), Some(count), "{} {}", context, word);
+                }
+                assert_eq!(stats.total(), 40, "{}", context);
+            }
+        }
+    }
+}
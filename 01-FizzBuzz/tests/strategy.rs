@@ -0,0 +1,52 @@
+use fizz_buzz::{write_fizz_buzz, FizzBuzz, Strategy};
+
+fn expected(start: u64, end: u64) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_fizz_buzz(&mut out, start..=end).unwrap();
+    out
+}
+
+// すべての実装方法が同じ出力になれば可
+#[test]
+fn test_strategies_equivalent() {
+    let ranges = [
+        (1, 0),
+        (1, 1),
+        (1, 15),
+        (1, 100_000),
+        (u64::MAX - 100, u64::MAX),
+    ];
+    for strategy in Strategy::ALL {
+        for (start, end) in ranges {
+            let mut out = Vec::new();
+            strategy.write(&mut out, start..=end).unwrap();
+            assert_eq!(
+                out,
+                expected(start, end),
+                "{} {}..={}",
+                strategy,
+                start,
+                end
+            );
+        }
+    }
+
+    let lines: Vec<_> = FizzBuzz::new(15).map(|value| value.to_string()).collect();
+    assert_eq!(
+        String::from_utf8(expected(1, 15))
+            .unwrap()
+            .lines()
+            .collect::<Vec<_>>(),
+        lines
+    );
+}
+
+#[test]
+fn test_parse() {
+    for strategy in Strategy::ALL {
+        assert_eq!(strategy.name().parse(), Ok(strategy));
+        assert_eq!(strategy.to_string(), strategy.name());
+    }
+    assert_eq!("IF".parse(), Ok(Strategy::If));
+    assert!("loop".parse::<Strategy>().is_err());
+}
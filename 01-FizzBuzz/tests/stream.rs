@@ -0,0 +1,82 @@
+use std::num::NonZeroUsize;
+
+use fizz_buzz::{transform, Config, InvalidLine, OnInvalid};
+
+fn run(
+    input: &[u8],
+    field: Option<usize>,
+    on_invalid: OnInvalid,
+) -> (String, Result<u64, InvalidLine>) {
+    let mut out = Vec::new();
+    let field = field.map(|n| NonZeroUsize::new(n).unwrap());
+    let result = transform(input, &mut out, &Config::default(), field, on_invalid).unwrap();
+    (String::from_utf8(out).unwrap(), result)
+}
+
+#[test]
+fn test_transform() {
+    let (out, result) = run(b"9\n10\n-15\n 7 \n+3\n", None, OnInvalid::Error);
+    assert_eq!(out, "Fizz\nBuzz\nFizzBuzz\n7\nFizz\n");
+    assert_eq!(result, Ok(5));
+
+    // CRLFや, 最終行の改行がない入力もLFで出力する
+    let (out, _) = run(b"1\r\n3", None, OnInvalid::Error);
+    assert_eq!(out, "1\nFizz\n");
+
+    // u64を越える数値
+    let (out, _) = run(b"1000000000000000000000000000015\n", None, OnInvalid::Error);
+    assert_eq!(out, "Buzz\n");
+
+    assert_eq!(run(b"", None, OnInvalid::Error), (String::new(), Ok(0)));
+}
+
+#[test]
+fn test_transform_field() {
+    let input = b"id,n,note\n1,3,x\n2,10\n3, 15 ,y\n";
+    let (out, result) = run(input, Some(2), OnInvalid::Pass);
+    assert_eq!(out, "id,n,note\n1,Fizz,x\n2,Buzz\n3,FizzBuzz,y\n");
+    assert_eq!(result, Ok(4));
+
+    let (out, _) = run(b"7,9\n", Some(1), OnInvalid::Error);
+    assert_eq!(out, "7,9\n");
+
+    // 引用符で囲まれた列の中のカンマでは区切らない
+    let input = b"\"a,b\",3,x\n\"say \"\"hi\"\", ok\",\"5\"\n";
+    let (out, result) = run(input, Some(2), OnInvalid::Error);
+    assert_eq!(out, "\"a,b\",Fizz,x\n\"say \"\"hi\"\", ok\",Buzz\n");
+    assert_eq!(result, Ok(2));
+}
+
+#[test]
+fn test_transform_invalid() {
+    let input = b"3\nfoo\n\n5\n";
+    assert_eq!(
+        run(input, None, OnInvalid::Pass),
+        ("Fizz\nfoo\n\nBuzz\n".to_string(), Ok(4))
+    );
+    assert_eq!(
+        run(input, None, OnInvalid::Skip),
+        ("Fizz\nBuzz\n".to_string(), Ok(4))
+    );
+
+    let (out, result) = run(input, None, OnInvalid::Error);
+    assert_eq!(out, "Fizz\n");
+    let invalid = result.unwrap_err();
+    assert_eq!(invalid.line(), 2);
+    assert_eq!(invalid.to_string(), r#"line 2: "foo" is not an integer"#);
+
+    let (_, result) = run(b"1,3\n2\n", Some(2), OnInvalid::Error);
+    assert_eq!(result.unwrap_err().to_string(), "line 2: missing field 2");
+
+    // UTF-8でない行もそのまま通せる
+    let mut out = Vec::new();
+    let result = transform(
+        &b"\xff\n3\n"[..],
+        &mut out,
+        &Config::default(),
+        None,
+        OnInvalid::Pass,
+    );
+    assert_eq!(result.unwrap(), Ok(2));
+    assert_eq!(out, b"\xff\nFizz\n");
+}
@@ -0,0 +1,70 @@
+use fizz_buzz::{fizz_buzz_value, FizzBuzz, FizzBuzzTable, FizzBuzzValue};
+
+// コンパイル時に求めた表
+static TABLE: FizzBuzzTable<10_000> = FizzBuzzTable::new();
+
+// コンパイル時に評価できなければ, ここでコンパイルエラーになる
+const _: () = {
+    assert!(matches!(fizz_buzz_value(0), FizzBuzzValue::FizzBuzz));
+    assert!(matches!(fizz_buzz_value(9), FizzBuzzValue::Fizz));
+    assert!(matches!(fizz_buzz_value(10), FizzBuzzValue::Buzz));
+    assert!(matches!(fizz_buzz_value(u64::MAX), FizzBuzzValue::FizzBuzz));
+    assert!(matches!(
+        FizzBuzzTable::<15>::VALUES[13],
+        FizzBuzzValue::Number(14)
+    ));
+};
+
+// 剰余から直接求めた値
+fn expected(n: u64) -> FizzBuzzValue {
+    match (n % 3, n % 5) {
+        (0, 0) => FizzBuzzValue::FizzBuzz,
+        (0, _) => FizzBuzzValue::Fizz,
+        (_, 0) => FizzBuzzValue::Buzz,
+        _ => FizzBuzzValue::Number(n),
+    }
+}
+
+// 先頭10,000個の値が, 剰余から直接求めた値と実行時に求めた値に一致すれば可
+#[test]
+fn test_table_matches_runtime() {
+    let runtime: Vec<_> = FizzBuzz::new(10_000).collect();
+    assert_eq!(TABLE.as_array().as_slice(), runtime.as_slice());
+
+    for n in 1..=10_000 {
+        assert_eq!(TABLE.get(n), Some(expected(n)), "{}", n);
+        assert_eq!(fizz_buzz_value(n), expected(n), "{}", n);
+    }
+    assert_eq!(
+        TABLE.as_array()[..15],
+        [
+            FizzBuzzValue::Number(1),
+            FizzBuzzValue::Number(2),
+            FizzBuzzValue::Fizz,
+            FizzBuzzValue::Number(4),
+            FizzBuzzValue::Buzz,
+            FizzBuzzValue::Fizz,
+            FizzBuzzValue::Number(7),
+            FizzBuzzValue::Number(8),
+            FizzBuzzValue::Fizz,
+            FizzBuzzValue::Buzz,
+            FizzBuzzValue::Number(11),
+            FizzBuzzValue::Fizz,
+            FizzBuzzValue::Number(13),
+            FizzBuzzValue::Number(14),
+            FizzBuzzValue::FizzBuzz,
+        ]
+    );
+}
+
+#[test]
+fn test_table_bounds() {
+    assert_eq!(TABLE.get(0), None);
+    assert_eq!(TABLE.get(10_001), None);
+    assert_eq!(TABLE.len(), 10_000);
+    assert_eq!(TABLE[14], FizzBuzzValue::FizzBuzz);
+    assert_eq!(TABLE.into_iter().count(), 10_000);
+
+    assert_eq!(FizzBuzzTable::<0>::new().get(1), None);
+    assert_eq!(FizzBuzzTable::<3>::VALUES, *FizzBuzzTable::<3>::default());
+}
@@ -0,0 +1,43 @@
+use fizz_buzz::{Template, TemplateError};
+
+fn render(template: &str, index: u64, n: i64, word: &str) -> String {
+    template.parse::<Template>().unwrap().render(index, n, word)
+}
+
+#[test]
+fn test_render() {
+    assert_eq!(render("{n}: {word}", 1, 15, "FizzBuzz"), "15: FizzBuzz");
+    assert_eq!(render("{word}", 1, 4, "4"), "4");
+    assert_eq!(render("", 1, 4, "4"), "");
+    assert_eq!(render("#{index:4} {n}", 7, -3, "Fizz"), "#0007 -3");
+    assert_eq!(render("{index}/{index:2}", 123, 0, ""), "123/123");
+    assert_eq!(render("{{{word}}} }}{{", 1, 3, "Fizz"), "{Fizz} }{");
+    assert_eq!(render("{n}→{word}", 1, 5, "バズ"), "5→バズ");
+}
+
+#[test]
+fn test_parse_error() {
+    let parse = |s: &str| s.parse::<Template>().unwrap_err();
+    assert_eq!(
+        parse("{n}: {value}"),
+        TemplateError::UnknownPlaceholder("value".to_string())
+    );
+    assert_eq!(
+        parse("{N}"),
+        TemplateError::UnknownPlaceholder("N".to_string())
+    );
+    assert_eq!(
+        parse("{n:3}"),
+        TemplateError::UnknownPlaceholder("n:3".to_string())
+    );
+    assert_eq!(parse("{word"), TemplateError::Unclosed);
+    assert_eq!(parse("word}"), TemplateError::UnmatchedBrace);
+    assert_eq!(
+        parse("{index:-1}"),
+        TemplateError::InvalidWidth("-1".to_string())
+    );
+    assert_eq!(
+        parse("{value}").to_string(),
+        "unknown placeholder `{value}` (expected {n}, {word} or {index})"
+    );
+}
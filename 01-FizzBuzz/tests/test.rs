@@ -1,4 +1,10 @@
-use assert_cmd::Command;
+// 元からある`cmd.args(&[...])`の書き方はそのままにする
+#![allow(clippy::needless_borrows_for_generic_args)]
+
+mod common;
+
+use assert_cmd::{assert::OutputAssertExt, Command};
+use common::TempDir;
 use indoc::indoc;
 
 const BINARY_NAME: &str = "fizz-buzz";
@@ -14,6 +20,17 @@ fn test_fizz_buzz_0() {
     cmd.assert().stdout("").success();
 }
 
+// 増分を指定しても, 0の場合は何も出力しなければ可
+#[test]
+fn test_fizz_buzz_0_step() {
+    for step in ["1", "2"] {
+        let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+        cmd.args(["0", "--step", step]);
+        cmd.assert().stdout("").success();
+    }
+}
+
 // 10までのFizzBuzzが出力されれば可 (可変対応確認)
 #[test]
 fn test_fizz_buzz_10() {
@@ -60,3 +77,1030 @@ fn test_fizz_buzz_15() {
     cmd.args(&["15"]);
     cmd.assert().stdout(expected).success();
 }
+
+// ルールを指定した場合は, 割り切れたルールの単語が指定順に連結されれば可
+#[test]
+fn test_fizz_buzz_custom_rules() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let expected = indoc! {r#"
+        1
+        Bazz
+        Fizz
+        Bazz
+        Buzz
+        FizzBazz
+        7
+    "#};
+    cmd.args([
+        "7", "--rule", "3=Fizz", "--rule", "5=Buzz", "--rule", "2=Bazz",
+    ]);
+    cmd.assert().stdout(expected).success();
+}
+
+// 除数0のルールは受け付けない
+#[test]
+fn test_fizz_buzz_zero_divisor() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    cmd.args(["10", "--rule", "0=Zero"]);
+    cmd.assert().failure();
+}
+
+// 割り切れるか以外のルール (数字を含む, 素数) も指定できれば可
+#[test]
+fn test_fizz_buzz_predicate_rules() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let expected = indoc! {r#"
+        1
+        Prime
+        FizzPrime
+        4
+        Prime
+        6
+        Prime
+        8
+        9
+        10
+        Prime
+        12
+        FizzPrime
+    "#};
+    cmd.args(["13", "--rule", "contains:3=Fizz", "--rule", "prime=Prime"]);
+    cmd.assert().stdout(expected).success();
+}
+
+// 割り切れるか以外のルールは集計できない
+#[test]
+fn test_fizz_buzz_predicate_rules_stats() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    cmd.args(["100", "--stats", "--rule", "prime=Prime"]);
+    cmd.assert().stdout("").failure();
+}
+
+// テストごとの一時ディレクトリに設定ファイルを作る
+fn config_dir(name: &str, file: &str, content: &str) -> TempDir {
+    let dir = TempDir::new(name);
+    std::fs::write(dir.join(file), content).unwrap();
+    dir
+}
+
+// 設定ファイルで, ルール, 単語の区切り, 該当しない場合の値を指定できれば可
+#[test]
+fn test_fizz_buzz_config() {
+    let dir = config_dir(
+        "config",
+        "rules.toml",
+        indoc! {r#"
+            separator = " "
+            fallback = "-"
+
+            [[rules]]
+            divisor = 3
+            word = "Fizz"
+
+            [[rules]]
+            divisor = 5
+            word = "Buzz"
+        "#},
+    );
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let expected = indoc! {r#"
+        -
+        -
+        Fizz
+        -
+        Buzz
+        Fizz
+        -
+        -
+        Fizz
+        Buzz
+        -
+        Fizz
+        -
+        -
+        Fizz Buzz
+    "#};
+    cmd.args(["15", "--config"]).arg(dir.join("rules.toml"));
+    cmd.assert().stdout(expected).success();
+}
+
+// 作業ディレクトリのfizzbuzz.tomlを読み込み, --ruleはそのルールより優先されれば可
+#[test]
+fn test_fizz_buzz_default_config() {
+    let dir = config_dir(
+        "default-config",
+        "fizzbuzz.toml",
+        indoc! {r#"
+            separator = "+"
+
+            [[rules]]
+            divisor = 2
+            word = "Foo"
+
+            [[rules]]
+            divisor = 3
+            word = "Bar"
+        "#},
+    );
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.current_dir(&dir).args(["6"]);
+    cmd.assert()
+        .stdout("1\nFoo\nBar\nFoo\n5\nFoo+Bar\n")
+        .success();
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.current_dir(&dir)
+        .args(["at", "15", "--rule", "3=Fizz", "--rule", "5=Buzz"]);
+    cmd.assert().stdout("Fizz+Buzz\n").success();
+}
+
+// 設定ファイルの誤りは, 該当するルールを示して終了すれば可
+#[test]
+fn test_fizz_buzz_invalid_config() {
+    let dir = config_dir(
+        "invalid-config",
+        "rules.json",
+        r#"{"rules": [{"divisor": 3, "word": "Fizz"}, {"kind": "odd", "word": "Odd"}]}"#,
+    );
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    cmd.args(["15", "--config"]).arg(dir.join("rules.json"));
+    let output = cmd.assert().stdout("").failure().get_output().clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("rule #2 (word = \"Odd\"): unknown rule kind `odd`"),
+        "{}",
+        stderr
+    );
+}
+
+// どの実装方法でも, 出力は既定の実装と同一であれば可
+#[test]
+fn test_fizz_buzz_strategy() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    let expected = cmd.arg("1000").output().unwrap().stdout;
+
+    for strategy in ["match", "if", "concat", "table"] {
+        let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+        cmd.args(["1000", "--strategy", strategy]);
+        cmd.assert().stdout(expected.clone()).success();
+    }
+}
+
+// 通常のFizzBuzz以外のルールでは, 実装方法は指定できない
+#[test]
+fn test_fizz_buzz_strategy_rules() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    cmd.args(["15", "--strategy", "if", "--rule", "7=Bazz"]);
+    cmd.assert().stdout("").failure();
+}
+
+// 各実装方法の1つの値あたりの時間が出力されれば可
+#[test]
+fn test_fizz_buzz_bench() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    cmd.args([
+        "bench",
+        "--n",
+        "1000",
+        "--strategy",
+        "match",
+        "--strategy",
+        "concat",
+    ]);
+    let output = cmd.assert().success().get_output().clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let names: Vec<_> = stdout
+        .lines()
+        .map(|line| line.split_whitespace().next().unwrap())
+        .collect();
+    assert_eq!(names, ["match", "concat"]);
+    assert!(stdout.lines().all(|line| line.ends_with(" ns/value")));
+}
+
+// 標準入力から読んだ整数を1行ずつ判定できれば可
+#[test]
+fn test_fizz_buzz_stdin() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    cmd.args(["--stdin"]).write_stdin("100\n101\n102\n105\n");
+    cmd.assert().stdout("Buzz\n101\nFizz\nFizzBuzz\n").success();
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["--stdin", "--field", "2", "--on-invalid", "pass"])
+        .write_stdin("name,n\na,3\nb,4\n");
+    cmd.assert().stdout("name,n\na,Fizz\nb,4\n").success();
+}
+
+// 整数として解析できない行は, 行番号を示して終了すれば可
+#[test]
+fn test_fizz_buzz_stdin_invalid() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    cmd.args(["--stdin"]).write_stdin("3\nthree\n5\n");
+    let output = cmd.assert().stdout("Fizz\n").failure().get_output().clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("line 2: \"three\" is not an integer"),
+        "{}",
+        stderr
+    );
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["--stdin", "--on-invalid", "skip"])
+        .write_stdin("3\nthree\n5\n");
+    cmd.assert().stdout("Fizz\nBuzz\n").success();
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["10", "--field", "2"]);
+    cmd.assert().stdout("").failure();
+}
+
+// 出力先が途中で閉じられても (`| head`など), パニックせず正常終了すれば可
+#[test]
+fn test_fizz_buzz_broken_pipe() {
+    use std::io::Read;
+    use std::process::Stdio;
+
+    for args in [
+        &["100000000"][..],
+        &["100000000", "--jobs", "2"],
+        &["10000000", "--format", "csv"],
+    ] {
+        let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin(BINARY_NAME))
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect(BINARY_LOAD_FAILED);
+
+        let mut head = [0; 16];
+        child.stdout.take().unwrap().read_exact(&mut head).unwrap();
+        let output = child.wait_with_output().unwrap();
+
+        assert!(output.status.success(), "{:?}: {:?}", args, output.status);
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "", "{:?}", args);
+    }
+}
+
+// 書き込みに失敗した場合は, 1行のエラーを表示して終了コード74で終了すれば可
+#[cfg(target_os = "linux")]
+#[test]
+fn test_fizz_buzz_write_error() {
+    let mut cmd = std::process::Command::new(assert_cmd::cargo::cargo_bin(BINARY_NAME));
+
+    cmd.arg("100000")
+        .stdout(std::fs::File::create("/dev/full").unwrap());
+    let output = cmd.assert().code(74).get_output().clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.starts_with("error: failed to write output: "),
+        "{}",
+        stderr
+    );
+    assert_eq!(stderr.lines().count(), 1);
+}
+
+// 読み込みに失敗した場合は, 書き込みの失敗と異なる終了コード66で終了すれば可
+#[cfg(target_os = "linux")]
+#[test]
+fn test_fizz_buzz_read_error() {
+    for args in [&["--stdin"][..], &["check", "--n", "15"]] {
+        let mut cmd = std::process::Command::new(assert_cmd::cargo::cargo_bin(BINARY_NAME));
+
+        // ディレクトリは開けても読み込めない
+        cmd.args(args).stdin(std::fs::File::open("/").unwrap());
+        let output = cmd.assert().code(66).get_output().clone();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(
+            stderr.starts_with("error: failed to read input: "),
+            "{}",
+            stderr
+        );
+    }
+}
+
+// 書式を指定した場合は, 各行がその書式で出力されれば可
+#[test]
+fn test_fizz_buzz_template() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let expected = indoc! {r#"
+        [01] 13: 13
+        [02] 14: 14
+        [03] 15: FizzBuzz
+    "#};
+    cmd.args([
+        "--from",
+        "13",
+        "--to",
+        "15",
+        "--template",
+        "[{index:2}] {n}: {word}",
+    ]);
+    cmd.assert().stdout(expected).success();
+}
+
+// 不明なプレースホルダは, 何も出力せずにエラーとなれば可
+#[test]
+fn test_fizz_buzz_template_unknown_placeholder() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    cmd.args(["15", "--template", "{n}: {value}"]);
+    cmd.assert().stdout("").code(2);
+}
+
+// 言語を指定した場合は, その言語の単語で出力されれば可
+#[test]
+fn test_fizz_buzz_locale() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let expected = indoc! {r#"
+        1
+        2
+        フィズ
+        4
+        バズ
+        フィズ
+        7
+        8
+        フィズ
+        バズ
+        11
+        フィズ
+        13
+        14
+        フィズバズ
+    "#};
+    cmd.args(["15", "--locale", "ja"]);
+    cmd.assert().stdout(expected).success();
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["at", "15", "--locale", "de"]);
+    cmd.assert().stdout("FissBuss\n").success();
+}
+
+// ゲームは標準入力から回答を読み, 最後に結果を表示すれば可
+#[test]
+fn test_fizz_buzz_play() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    cmd.args([
+        "play", "--n", "10", "--rule", "3=Fizz", "--rule", "5=Buzz", "--rule", "7=Bazz",
+    ])
+    .write_stdin("2\n4\nFizz\n8\nBuzz\n");
+    let output = cmd.assert().success().get_output().clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("me: Bazz\n"), "{}", stdout);
+    assert!(stdout.contains("--- completed ---\n"), "{}", stdout);
+    assert!(stdout.contains("score: 5/5 (100.0%)\n"), "{}", stdout);
+    assert!(stdout.contains("best streak: 5\n"), "{}", stdout);
+    assert!(stdout.contains("reaction time: average "), "{}", stdout);
+}
+
+// 間違えてライフがなくなるとゲームを終了すれば可
+#[test]
+fn test_fizz_buzz_play_game_over() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    cmd.args(["play", "--lives", "1"])
+        .write_stdin("2\n4\n6\n8\n");
+    let output = cmd.assert().success().get_output().clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(
+        stdout.contains("wrong: 6 is \"Fizz\", not \"6\" (lives: 0)\n"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("--- game over ---\nscore: 2/3 (66.7%)\nbest streak: 2\n"),
+        "{}",
+        stdout
+    );
+}
+
+// 制限時間内に回答がなければ時間切れになれば可
+#[test]
+fn test_fizz_buzz_play_timeout() {
+    use std::process::Stdio;
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin(BINARY_NAME))
+        .args(["play", "--lives", "1", "--timeout", "0.2"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect(BINARY_LOAD_FAILED);
+
+    // 標準入力を開いたまま, 何も入力しない
+    let stdin = child.stdin.take();
+    let output = child.wait_with_output().unwrap();
+    drop(stdin);
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success());
+    assert!(
+        stdout.contains("time up: 2 is \"2\" (lives: 0)\n"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("--- game over ---\nscore: 0/1 (0.0%)\n"),
+        "{}",
+        stdout
+    );
+}
+
+// 空いているポートで起動したサーバが, 指定のルールで応答すれば可
+#[test]
+fn test_fizz_buzz_serve() {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpStream,
+        process::Stdio,
+    };
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin(BINARY_NAME))
+        .args(["serve", "--bind", "127.0.0.1:0", "--rule", "2=Even"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect(BINARY_LOAD_FAILED);
+
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    let addr = line
+        .trim_end()
+        .strip_prefix("listening on http://")
+        .unwrap();
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream
+        .write_all(b"GET /fizzbuzz/4 HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+    assert!(response.ends_with("\r\n\r\nEven\n"), "{}", response);
+}
+
+// 使用中のアドレスでは, エラーとして終了すれば可
+#[test]
+fn test_fizz_buzz_serve_bind_error() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    cmd.args(["serve", "--bind", &addr]);
+    let output = cmd.assert().code(1).get_output().clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.starts_with(&format!("error: failed to bind {}: ", addr)),
+        "{}",
+        stderr
+    );
+}
+
+// スレッド数を指定しても, 出力は1スレッドの場合と同一であれば可
+#[test]
+fn test_fizz_buzz_jobs() {
+    for n in ["0", "10", "15", "3000000"] {
+        let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+        let expected = cmd.arg(n).output().unwrap().stdout;
+
+        for jobs in ["1", "2", "3", "8"] {
+            let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+            cmd.args([n, "--jobs", jobs]);
+            cmd.assert().stdout(expected.clone()).success();
+        }
+    }
+}
+
+// スレッド数0は受け付けない
+#[test]
+fn test_fizz_buzz_zero_jobs() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    cmd.args(["10", "--jobs", "0"]);
+    cmd.assert().failure();
+}
+
+// 並列に生成しない範囲や形式とスレッド数を併用した場合は, 無視せずに引数のエラーとなれば可
+#[test]
+fn test_fizz_buzz_jobs_conflicts() {
+    for args in [
+        &["--from", "1", "--to", "10", "--jobs", "2"][..],
+        &["--count", "10", "--jobs", "2"],
+        &["10", "--step", "2", "--jobs", "2"],
+        &["10", "--format", "csv", "--jobs", "2"],
+    ] {
+        let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+        cmd.args(args);
+        cmd.assert().stdout("").code(2);
+    }
+}
+
+// JSON形式では, 数値と値の組の配列が出力されれば可
+#[test]
+fn test_fizz_buzz_format_json() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let expected = indoc! {r#"
+        [
+        {"n":1,"value":"1"},
+        {"n":2,"value":"2"},
+        {"n":3,"value":"Fizz"}
+        ]
+    "#};
+    cmd.args(["3", "--format", "json"]);
+    cmd.assert().stdout(expected).success();
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["0", "--format", "json"]);
+    cmd.assert().stdout("[]\n").success();
+}
+
+// NDJSON形式では, 1行に1つのオブジェクトが出力されれば可
+#[test]
+fn test_fizz_buzz_format_ndjson() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let expected = indoc! {r#"
+        {"n":14,"value":"14"}
+        {"n":15,"value":"FizzBuzz"}
+    "#};
+    let output = cmd.args(["15", "--format", "ndjson"]).output().unwrap();
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .ends_with(expected));
+}
+
+// CSV形式では, ヘッダと各行が出力されれば可
+#[test]
+fn test_fizz_buzz_format_csv() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let expected = indoc! {r#"
+        n,value
+        1,1
+        2,2
+        3,Fizz
+        4,4
+        5,Buzz
+    "#};
+    cmd.args(["5", "--format", "csv"]);
+    cmd.assert().stdout(expected).success();
+}
+
+// 表形式では, 列の幅が揃って出力されれば可
+#[test]
+fn test_fizz_buzz_format_table() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let expected = indoc! {r#"
+        |  n | value    |
+        |---:|:---------|
+        |  9 | Fizz     |
+        | 10 | Buzz     |
+    "#};
+    let output = cmd.args(["10", "--format", "table"]).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<_> = stdout.lines().collect();
+    let expected: Vec<_> = expected.lines().collect();
+    assert_eq!(lines[..2], expected[..2]);
+    assert_eq!(lines[lines.len() - 2..], expected[2..]);
+}
+
+// 負の数を含む範囲では, 0と負の数も数学的に判定されれば可
+#[test]
+fn test_fizz_buzz_negative_range() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let expected = indoc! {r#"
+        Buzz
+        -4
+        Fizz
+        -2
+        -1
+        FizzBuzz
+        1
+    "#};
+    cmd.args(["--from", "-5", "--to", "1"]);
+    cmd.assert().stdout(expected).success();
+}
+
+// 開始値が終了値より大きい場合は, 増分ごとに降順で出力されれば可
+#[test]
+fn test_fizz_buzz_descending_step() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let expected = indoc! {r#"
+        Buzz
+        Fizz
+        2
+    "#};
+    cmd.args(["--from", "10", "--to", "1", "--step", "4"]);
+    cmd.assert().stdout(expected).success();
+}
+
+// 範囲外の値はパニックせず, エラーとして終了すれば可
+#[test]
+fn test_fizz_buzz_range_overflow() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["--to", "18446744073709551615"]);
+    cmd.assert().stdout("").code(2);
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["18446744073709551615", "--step", "2"]);
+    cmd.assert().stdout("").code(2);
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args([
+        "--from",
+        "9223372036854775806",
+        "--to",
+        "9223372036854775807",
+    ]);
+    cmd.assert().stdout("Fizz\n9223372036854775807\n").success();
+}
+
+// u64を越える数値から指定個数が出力されれば可
+#[test]
+fn test_fizz_buzz_big() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let expected = indoc! {r#"
+        Buzz
+        10000000000000000000000000000000000000001
+        Fizz
+    "#};
+    cmd.args(["--from", "10^40", "--count", "3"]);
+    cmd.assert().stdout(expected).success();
+}
+
+// 各単語の個数が出力されれば可 (巨大な範囲も即座に計算できること)
+#[test]
+fn test_fizz_buzz_stats() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let expected = indoc! {r#"
+        Fizz: 266666666666666667
+        Buzz: 133333333333333334
+        FizzBuzz: 66666666666666666
+        Number: 533333333333333333
+    "#};
+    cmd.args(["--stats", "10^18"]);
+    cmd.assert().stdout(expected).success();
+}
+
+// u64に収まらない累乗は, 展開せずに直ちに引数の誤りとなれば可
+#[test]
+fn test_fizz_buzz_number_too_large() {
+    for args in [
+        &["10^300000"][..],
+        &["--stats", "10^200000"],
+        &["check", "--n", "10^100000"],
+        &["2^64"],
+    ] {
+        let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+        cmd.args(args).timeout(std::time::Duration::from_secs(5));
+        let output = cmd.output().unwrap();
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(
+            stderr.contains("is too large (must be at most 18446744073709551615)"),
+            "{}",
+            stderr
+        );
+    }
+}
+
+// 1つの数値に対する値が出力されれば可
+#[test]
+fn test_fizz_buzz_at() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    cmd.args(["at", "123456789"]);
+    cmd.assert().stdout("Fizz\n").success();
+}
+
+// n番目に単語が出力される数値が出力されれば可
+#[test]
+fn test_fizz_buzz_find() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["find", "--word", "Buzz", "--nth", "1000"]);
+    cmd.assert().stdout("7495\n").success();
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args([
+        "find", "--word", "Bar", "--rule", "2=Foo", "--rule", "4=Bar",
+    ]);
+    cmd.assert().stdout("").failure();
+}
+
+// 正しい解答は終了コード0で終了すれば可
+#[test]
+fn test_fizz_buzz_check_ok() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let answer = indoc! {r#"
+        1
+        2
+        Fizz
+        4
+        Buzz
+    "#};
+    cmd.args(["check", "--n", "5"]).write_stdin(answer);
+    cmd.assert().stdout("ok: 5 lines match\n").success();
+}
+
+// 誤った解答は, 最初の相違点を表示して終了コード1で終了すれば可
+#[test]
+fn test_fizz_buzz_check_mismatch() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let answer = indoc! {r#"
+        1
+        2
+        3
+        4
+        Buzz
+    "#};
+    cmd.args(["check", "--n", "5"]).write_stdin(answer);
+    cmd.assert()
+        .stdout("line 3: expected \"Fizz\", found \"3\"\n")
+        .code(1);
+}
+
+// 出力から推定したルールが, 設定ファイルの形式で出力されれば可
+#[test]
+fn test_fizz_buzz_infer() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let transcript = indoc! {r#"
+        Fizz
+        Buzz
+        11
+        Fizz
+        13
+        Bazz
+        FizzBuzz
+        16
+        17
+        Fizz
+        19
+        Buzz
+        FizzBazz
+    "#};
+    cmd.args(["infer", "--from", "9"]).write_stdin(transcript);
+    cmd.assert()
+        .stdout(indoc! {r#"
+            # inferred from 13 lines (9..=21)
+
+            [[rules]]
+            divisor = 3
+            word = "Fizz"
+
+            [[rules]]
+            divisor = 5
+            word = "Buzz"
+
+            [[rules]]
+            divisor = 7
+            word = "Bazz"
+        "#})
+        .success();
+}
+
+// 矛盾する行があれば, エラーを表示して終了コード1で終了すれば可
+#[test]
+fn test_fizz_buzz_infer_contradiction() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    cmd.arg("infer")
+        .write_stdin("1\n2\nFizz\n4\nBuzz\nFizz\n7\n8\n9\n");
+    let output = cmd.assert().code(1).get_output().clone();
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "error: line 9: expected \"Fizz\" (multiple of 3), found \"9\"\n"
+    );
+}
+
+// `--numerals kanji`で, ルールに該当しない数値のみ漢数字で出力されれば可
+#[test]
+fn test_fizz_buzz_numerals() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    let expected = indoc! {r#"
+        十一
+        Fizz
+        十三
+        十四
+        FizzBuzz
+    "#};
+    cmd.args(["--from", "11", "--to", "15", "--numerals", "kanji"]);
+    cmd.assert().stdout(expected).success();
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["at", "14", "--numerals", "roman"]);
+    cmd.assert().stdout("XIV\n").success();
+}
+
+// `--radix 2`で2進数が, `--rule-base`でルールも16進数として読まれれば可
+#[test]
+fn test_fizz_buzz_radix() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    cmd.args(["6", "--radix", "2"]);
+    cmd.assert()
+        .stdout("1\n10\nFizz\n100\nBuzz\nFizz\n")
+        .success();
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    let expected = indoc! {r#"
+        9
+        Ten
+        b
+        c
+        d
+        e
+        Eff
+        10
+    "#};
+    cmd.args(["--from", "9", "--to", "16", "--radix", "16", "--rule-base"])
+        .args(["--rule", "a=Ten", "--rule", "contains:f=Eff"]);
+    cmd.assert().stdout(expected).success();
+
+    // 基数と合わない数字, 範囲外の基数, `--radix`のない`--rule-base`は引数の誤り
+    for args in [
+        &[
+            "5",
+            "--radix",
+            "2",
+            "--rule-base",
+            "--rule",
+            "contains:2=Two",
+        ][..],
+        &["5", "--radix", "37"],
+        &["5", "--rule-base"],
+    ] {
+        let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+        cmd.args(args);
+        cmd.assert().stdout("").code(2);
+    }
+}
+
+// 既定 (auto) ではパイプへの出力に色が付かず, `--color always`では`NO_COLOR`があっても色が付けば可
+#[test]
+fn test_fizz_buzz_color() {
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+
+    cmd.args(["--from", "14", "--to", "15"])
+        .env_remove("NO_COLOR");
+    cmd.assert().stdout("14\nFizzBuzz\n").success();
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["--from", "14", "--to", "15", "--color", "always"])
+        .env("NO_COLOR", "1");
+    cmd.assert()
+        .stdout("14\n\x1b[1;33mFizz\x1b[0m\x1b[1;36mBuzz\x1b[0m\n")
+        .success();
+
+    // 構造化された形式には色を付けない
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["at", "3", "--color", "never"]);
+    cmd.assert().stdout("Fizz\n").success();
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args([
+        "--from", "3", "--to", "3", "--format", "ndjson", "--color", "always",
+    ]);
+    cmd.assert()
+        .stdout("{\"n\":3,\"value\":\"Fizz\"}\n")
+        .success();
+}
+
+// `--output`で拡張子に応じて圧縮したファイルへ出力し, `--resume`で途中から続けられれば可
+#[test]
+fn test_fizz_buzz_output() {
+    let dir = config_dir("output", "expected.txt", "");
+    let expected = indoc! {r#"
+        1
+        2
+        Fizz
+        4
+        Buzz
+        Fizz
+        7
+    "#};
+
+    let path = dir.join("out.txt");
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["7", "--output"]).arg(&path);
+    cmd.assert().stdout("").success();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), expected);
+
+    // 途中までの行は書き直す
+    std::fs::write(&path, "1\n2\nFizz\n4\nBu").unwrap();
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["7", "--resume", "--output"]).arg(&path);
+    cmd.assert().stdout("").success();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), expected);
+
+    let gz = dir.join("out.txt.gz");
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["7", "--output"]).arg(&gz);
+    cmd.assert().success();
+    assert_eq!(&std::fs::read(&gz).unwrap()[..2], [0x1f, 0x8b]);
+}
+
+// エラーで中断した場合も, 圧縮したファイルがそこまでの出力を終端まで含めば可
+#[test]
+fn test_fizz_buzz_output_error() {
+    use std::io::Read;
+
+    let dir = config_dir("output-error", "in.txt", "");
+    let decode = |path: &std::path::Path| {
+        let mut s = String::new();
+        let file = std::fs::File::open(path).unwrap();
+        match path.extension().unwrap().to_str().unwrap() {
+            "gz" => flate2::read::MultiGzDecoder::new(file)
+                .read_to_string(&mut s)
+                .unwrap(),
+            _ => zstd::Decoder::new(file)
+                .unwrap()
+                .read_to_string(&mut s)
+                .unwrap(),
+        };
+        s
+    };
+
+    // 整数でない入力行
+    let gz = dir.join("stdin.txt.gz");
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["--stdin", "--output"])
+        .arg(&gz)
+        .write_stdin("1\n2\nthree\n4\n");
+    cmd.assert()
+        .stderr("error: line 3: \"three\" is not an integer\n")
+        .code(1);
+    assert_eq!(decode(&gz), "1\n2\n");
+
+    // u64を越える数値に適用できないルール
+    let zst = dir.join("big.txt.zst");
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args([
+        "--from",
+        "18446744073709551614",
+        "--count",
+        "3",
+        "--rule",
+        "prime=Prime",
+    ])
+    .arg("--output")
+    .arg(&zst);
+    cmd.assert().code(1);
+    assert_eq!(decode(&zst), "18446744073709551614\n18446744073709551615\n");
+
+    // 引数の検証エラー
+    let gz = dir.join("stats.txt.gz");
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["--stats", "--from", "10^40", "--count", "3", "--output"])
+        .arg(&gz);
+    cmd.assert().code(2);
+    assert_eq!(decode(&gz), "");
+}
+
+// 既存のファイルの行が正しい出力と一致しなければ, 再開せずに終了コード1で終了すれば可
+#[test]
+fn test_fizz_buzz_resume_mismatch() {
+    let dir = config_dir("resume-mismatch", "out.txt", "1\n2\n3\n");
+    let path = dir.join("out.txt");
+
+    let mut cmd = Command::cargo_bin(BINARY_NAME).expect(BINARY_LOAD_FAILED);
+    cmd.args(["7", "--resume", "--output"]).arg(&path);
+    let output = cmd.assert().code(1).get_output().clone();
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        format!(
+            "error: cannot resume {}: line 3 is \"3\", expected \"Fizz\"\n",
+            path.display()
+        )
+    );
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "1\n2\n3\n");
+}
@@ -0,0 +1,41 @@
+use fizz_buzz::{FizzBuzz, FizzBuzzValue};
+use serde::{Deserialize, Serialize};
+
+// JSONの中の値として書き出し, 読み戻すと元の値に戻れば可
+#[test]
+fn test_json_round_trip() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Fixture {
+        n: u64,
+        value: FizzBuzzValue,
+    }
+
+    let fixtures: Vec<_> = FizzBuzz::new(15)
+        .enumerate()
+        .map(|(i, value)| Fixture {
+            n: i as u64 + 1,
+            value,
+        })
+        .collect();
+    let json = serde_json::to_string(&fixtures).unwrap();
+    assert!(json.starts_with(r#"[{"n":1,"value":"1"},{"n":2,"value":"2"},{"n":3,"value":"Fizz"}"#));
+
+    let parsed: Vec<Fixture> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, fixtures);
+}
+
+#[test]
+fn test_deserialize() {
+    let parse = |json: &str| serde_json::from_str::<FizzBuzzValue>(json);
+    assert_eq!(parse(r#""FizzBuzz""#).unwrap(), FizzBuzzValue::FizzBuzz);
+    assert_eq!(parse(r#""42""#).unwrap(), FizzBuzzValue::Number(42));
+    // 数値のままでも受け付ける
+    assert_eq!(parse("42").unwrap(), FizzBuzzValue::Number(42));
+
+    assert_eq!(
+        parse(r#""Bazz""#).unwrap_err().to_string(),
+        "expected a number, Fizz, Buzz or FizzBuzz at line 1 column 6"
+    );
+    assert!(parse("-1").is_err());
+    assert!(parse("null").is_err());
+}
@@ -0,0 +1,121 @@
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+    num::NonZeroUsize,
+    sync::Mutex,
+    thread,
+};
+
+use fizz_buzz::{write_fizz_buzz, write_parallel, write_with_rules, FizzBuzz, WordRule, CHUNK_LEN};
+
+fn expected(start: u64, end: u64) -> String {
+    FizzBuzz::range(start..=end)
+        .map(|v| format!("{}\n", v))
+        .collect()
+}
+
+fn written(start: u64, end: u64) -> String {
+    let mut out = Vec::new();
+    write_fizz_buzz(&mut out, start..=end).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+// 高速版の出力がイテレータの出力と一致すれば可 (周期の途中開始, 桁上がりを含む)
+#[test]
+fn test_write_matches_iter() {
+    for (start, end) in [
+        (1, 100),
+        (0, 31),
+        (7, 7),
+        (8, 22),
+        (95, 1005),
+        (999_999_990, 1_000_000_020),
+    ] {
+        assert_eq!(
+            written(start, end),
+            expected(start, end),
+            "{}..={}",
+            start,
+            end
+        );
+    }
+}
+
+// u64の上限まで書き出しても溢れなければ可
+#[test]
+fn test_write_upper_bound() {
+    let start = u64::MAX - 40;
+    assert_eq!(written(start, u64::MAX), expected(start, u64::MAX));
+}
+
+// 空の範囲では何も書き出さなければ可
+#[test]
+fn test_write_empty() {
+    let (start, end) = (10, 9);
+    assert_eq!(written(start, end), "");
+}
+
+// 任意ルール版も通常のルールでは同じ出力になれば可
+#[test]
+fn test_write_with_rules() {
+    let mut out = Vec::new();
+    write_with_rules(&mut out, 1..=100, &WordRule::classic()).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), expected(1, 100));
+}
+
+// 複数チャンクにまたがる範囲でも, 並列版の出力が逐次版と一致すれば可
+#[test]
+fn test_write_parallel() {
+    let end = CHUNK_LEN * 2 + 7;
+    let sequential = written(1, end);
+    for jobs in [1, 2, 3, 5] {
+        let mut out = Vec::new();
+        write_parallel(
+            &mut out,
+            1..=end,
+            NonZeroUsize::new(jobs).unwrap(),
+            |buf, chunk| write_fizz_buzz(buf, chunk),
+        )
+        .unwrap();
+        assert!(out == sequential.as_bytes(), "jobs = {}", jobs);
+    }
+}
+
+// チャンクの数によらず, 変換するスレッドは`jobs`個のみで, 結果は範囲の順に書き出されれば可
+#[test]
+fn test_write_parallel_workers() {
+    let threads = Mutex::new(HashSet::new());
+    let mut out = Vec::new();
+    write_parallel(
+        &mut out,
+        1..=CHUNK_LEN * 7,
+        NonZeroUsize::new(3).unwrap(),
+        |buf, chunk| {
+            threads.lock().unwrap().insert(thread::current().id());
+            writeln!(buf, "{}", chunk.start())
+        },
+    )
+    .unwrap();
+
+    let expected: String = (0..7).map(|i| format!("{}\n", i * CHUNK_LEN + 1)).collect();
+    assert_eq!(String::from_utf8(out).unwrap(), expected);
+    assert_eq!(threads.into_inner().unwrap().len(), 3);
+}
+
+// 変換のエラーは, それより前のチャンクを書き出した後に返れば可
+#[test]
+fn test_write_parallel_error() {
+    let mut out = Vec::new();
+    let result = write_parallel(
+        &mut out,
+        1..=CHUNK_LEN * 5,
+        NonZeroUsize::new(2).unwrap(),
+        |buf, chunk| match *chunk.start() {
+            start if start > CHUNK_LEN * 2 => Err(io::Error::other("render failed")),
+            start => writeln!(buf, "{}", start),
+        },
+    );
+
+    assert_eq!(result.unwrap_err().to_string(), "render failed");
+    assert_eq!(out, format!("1\n{}\n", CHUNK_LEN + 1).into_bytes());
+}